@@ -0,0 +1,171 @@
+use crate::{file_handling, time};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    FileError(#[from] file_handling::Error),
+    #[error("error: writing the audit log {0}")]
+    Write(PathBuf, #[source] std::io::Error),
+    #[error("invalid line {1} in the audit log {0}")]
+    Line(PathBuf, usize, #[source] serde_json::Error),
+}
+
+/// a request to the llm host and what came back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// when it was sent, in utc
+    pub time: String,
+    pub host: String,
+    pub model: String,
+    pub url: String,
+    /// the messages exactly as sent, none for requests without a body
+    pub messages: Option<Value>,
+    /// none when the host could not be reached
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// the raw body of the answer, the events of a stream one per line
+    pub response: String,
+    pub error: Option<String>,
+}
+impl Record {
+    pub fn new(host: &str, model: &str, url: &str, sent: SystemTime) -> Self {
+        Record {
+            time: time::utc(sent),
+            host: host.to_owned(),
+            model: model.to_owned(),
+            url: url.to_owned(),
+            messages: None,
+            status: None,
+            latency_ms: 0,
+            response: String::new(),
+            error: None,
+        }
+    }
+    pub fn took(&mut self, latency: Duration) {
+        self.latency_ms = latency.as_millis() as u64;
+    }
+    /// the record as [`AuditLog::write`] writes it, the `secrets` redacted
+    pub fn redacted(&self, secrets: &[&str]) -> Record {
+        let line = redact(
+            serde_json::to_string(self).expect("serializable record"),
+            secrets,
+        );
+        serde_json::from_str(&line).unwrap_or_else(|_| self.clone())
+    }
+    /// the text of the last message, the one translated
+    fn text(&self) -> &str {
+        let Some(Value::Array(messages)) = &self.messages else {
+            return "";
+        };
+        messages
+            .last()
+            .and_then(|m| m["content"].as_str())
+            .unwrap_or_default()
+    }
+}
+
+/// `line` with every occurrence of the `secrets` redacted
+fn redact(mut line: String, secrets: &[&str]) -> String {
+    for secret in secrets.iter().filter(|s| !s.is_empty()) {
+        // the line holds it json escaped, and escaped twice inside a raw json response
+        let escaped = escape_json(secret);
+        line = line
+            .replace(&escape_json(&escaped), "<redacted>")
+            .replace(&escaped, "<redacted>");
+    }
+    line
+}
+/// `text` as it reads inside a json string
+fn escape_json(text: &str) -> String {
+    let quoted = serde_json::to_string(text).expect("strings serialize");
+    quoted[1..quoted.len() - 1].to_owned()
+}
+
+/// an opt-in jsonl file with a record of every request to the llm host
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pub path: PathBuf,
+}
+impl AuditLog {
+    /// appends `record` with every occurrence of the `secrets` redacted
+    pub fn write(&self, record: &Record, secrets: &[&str]) -> Result<(), Error> {
+        let line = redact(
+            serde_json::to_string(record).expect("serializable record"),
+            secrets,
+        );
+        let write_error = |e| Error::Write(self.path.clone(), e);
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(write_error)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(write_error)?;
+        writeln!(file, "{line}").map_err(write_error)
+    }
+    pub fn records(&self) -> Result<Vec<Record>, Error> {
+        let text = file_handling::read_from_file(&self.path)?;
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| Error::Line(self.path.clone(), i + 1, e))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct ShowConfig {
+    pub log: AuditLog,
+    /// dates like `2025-03-14`, or a prefix like `2025-03`
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// only the requests whose text contains it
+    pub key: Option<String>,
+    pub verbose: bool,
+}
+/// prints the records of the log that match, as they are stored
+pub fn show(config: ShowConfig) -> Result<(), Error> {
+    if config.verbose {
+        eprintln!("reading the audit log: {}", config.log.path.display());
+    }
+    for record in config.log.records()? {
+        let date = &record.time[..];
+        if config
+            .since
+            .as_ref()
+            .is_some_and(|since| date < since.as_str())
+        {
+            continue;
+        }
+        // the whole of the last day or month counts
+        if config
+            .until
+            .as_ref()
+            .is_some_and(|until| !date.starts_with(until.as_str()) && date > until.as_str())
+        {
+            continue;
+        }
+        if config
+            .key
+            .as_ref()
+            .is_some_and(|key| !record.text().contains(key.as_str()))
+        {
+            continue;
+        }
+        println!(
+            "{}",
+            serde_json::to_string(&record).expect("serializable record")
+        );
+    }
+    Ok(())
+}
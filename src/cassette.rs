@@ -0,0 +1,81 @@
+use crate::audit::{self, AuditLog, Record};
+use std::cell::RefCell;
+use std::env;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Audit(#[from] audit::Error),
+    #[error("set B3_RECORD or B3_REPLAY, not both")]
+    Both,
+    #[error("no unused {1} with the same model and messages in {0}")]
+    NotRecorded(PathBuf, String),
+    #[error("the recorded request to {0} failed: {1}")]
+    Failed(String, String),
+}
+
+/// request and answer pairs of the llm host, kept as the records of the audit
+/// log. recording writes every exchange to the file, replaying answers from it
+/// without a network
+#[derive(Debug, Clone)]
+pub enum Cassette {
+    Record(AuditLog),
+    Replay {
+        path: PathBuf,
+        records: Vec<Record>,
+        /// every recorded answer is given once
+        played: RefCell<Vec<bool>>,
+    },
+}
+impl Cassette {
+    /// the cassette `B3_RECORD` or `B3_REPLAY` points to
+    pub fn from_env() -> Result<Option<Cassette>, Error> {
+        match (env::var_os("B3_RECORD"), env::var_os("B3_REPLAY")) {
+            (Some(_), Some(_)) => Err(Error::Both),
+            (Some(path), None) => Ok(Some(Cassette::Record(AuditLog { path: path.into() }))),
+            (None, Some(path)) => Cassette::replay(path.into()).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+    pub fn replay(path: PathBuf) -> Result<Cassette, Error> {
+        let records = AuditLog { path: path.clone() }.records()?;
+        Ok(Cassette::Replay {
+            path,
+            played: RefCell::new(vec![false; records.len()]),
+            records,
+        })
+    }
+    /// the status and body recorded for the request of `record`, none when recording.
+    /// `record` is matched with the `secrets` redacted, as they were when recorded
+    pub fn play(&self, record: &Record, secrets: &[&str]) -> Option<Result<(u16, String), Error>> {
+        let Cassette::Replay {
+            path,
+            records,
+            played,
+        } = self
+        else {
+            return None;
+        };
+        let record = record.redacted(secrets);
+        let mut played = played.borrow_mut();
+        let found = records.iter().zip(played.iter_mut()).find(|(r, played)| {
+            !**played
+                && r.url == record.url
+                && r.model == record.model
+                && r.messages == record.messages
+        });
+        let Some((recorded, played)) = found else {
+            return Some(Err(Error::NotRecorded(path.clone(), record.url.clone())));
+        };
+        *played = true;
+        Some(match (recorded.status, &recorded.error) {
+            (Some(status), _) => Ok((status, recorded.response.clone())),
+            (None, error) => Err(Error::Failed(
+                recorded.url.clone(),
+                error.clone().unwrap_or_default(),
+            )),
+        })
+    }
+}
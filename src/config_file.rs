@@ -0,0 +1,239 @@
+use crate::file_handling;
+use std::env;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub const FILE_NAME: &str = "beetree.toml";
+pub const PROFILES_FILE_NAME: &str = "profiles.toml";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    FileError(#[from] file_handling::Error),
+    #[error("error: reading {0}")]
+    Parse(PathBuf, #[source] toml::de::Error),
+    #[error("unknown key {1} in {0}")]
+    UnknownKey(PathBuf, String),
+    #[error("{1} in {0} has to be a string, number, boolean or a list of them")]
+    Type(PathBuf, String),
+    #[error("keep {1} out of {0}, use a profile or the env")]
+    Secret(PathBuf, String),
+    #[error("no profile {1} in {0}")]
+    UnknownProfile(PathBuf, String),
+    #[error("no config directory to find {PROFILES_FILE_NAME} in")]
+    NoConfigDir,
+}
+
+/// a setting of the config file and the cli argument it gives the default of
+#[derive(Debug)]
+pub struct Key {
+    pub name: &'static str,
+    pub arg: &'static str,
+    /// relative to the config file
+    pub is_path: bool,
+    /// only allowed in profiles, never shown
+    pub secret: bool,
+    /// a table of names and values, given as `name: value` lines
+    pub map: bool,
+}
+const fn key(name: &'static str, arg: &'static str, is_path: bool) -> Key {
+    Key {
+        name,
+        arg,
+        is_path,
+        secret: false,
+        map: false,
+    }
+}
+pub const KEYS: [Key; 37] = [
+    key("base", "base_path", true),
+    key("languages", "languages", false),
+    // the language translated from
+    key("reference", "source", false),
+    key("file", "search_file", false),
+    key("format", "serializer", false),
+    key("layout", "layout", false),
+    // the provider the llm runs at
+    key("llm.host", "host", false),
+    key("llm.model", "model", false),
+    key("llm.endpoint", "endpoint", false),
+    key("llm.auth", "auth", false),
+    key("llm.auth_header", "auth_header", false),
+    Key {
+        map: true,
+        ..key("llm.headers", "headers", false)
+    },
+    key("llm.prompt_file", "prompt_file", true),
+    key("llm.glossary", "glossary", true),
+    key("llm.connect_timeout", "connect_timeout", false),
+    key("llm.timeout", "timeout", false),
+    key("llm.proxy", "proxy", false),
+    key("llm.ca_cert", "ca_certs", true),
+    key("llm.insecure", "insecure", false),
+    key("llm.check_model", "check_model", false),
+    key("llm.retries", "retries", false),
+    key("llm.temperature", "temperature", false),
+    key("llm.top_p", "top_p", false),
+    key("llm.seed", "seed", false),
+    key("llm.max_tokens", "max_tokens", false),
+    key("llm.stream", "stream", false),
+    key("llm.stats", "stats", false),
+    key("llm.prices", "prices", true),
+    key("llm.ledger", "ledger", true),
+    key("llm.no_ledger", "no_ledger", false),
+    // the most a single command may use
+    key("llm.max_tokens_total", "max_tokens_total", false),
+    key("llm.max_cost", "max_cost", false),
+    key("llm.audit_log", "audit_log", true),
+    Key {
+        secret: true,
+        ..key("llm.api_key", "api_key", false)
+    },
+    key("llm.api_key_cmd", "api_key_cmd", false),
+    key("llm.api_key_file", "api_key_file", true),
+    key("llm.api_key_keyring", "api_key_keyring", false),
+];
+
+/// per-project defaults, they only apply to what is not given on the cli or in
+/// the env. or the settings of a profile, which only give way to the cli
+#[derive(Debug)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub profile: Option<String>,
+    /// the argument id with its default, once for every element of a list of paths
+    pub values: Vec<(&'static str, String)>,
+}
+impl ConfigFile {
+    /// the defaults of the argument `id`
+    pub fn get(&self, id: &str) -> Vec<String> {
+        self.values
+            .iter()
+            .filter(|(i, _)| *i == id)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+    /// the `beetree.toml` of the current directory or the closest one above it
+    pub fn discover() -> Result<Option<ConfigFile>, Error> {
+        let Ok(cwd) = std::env::current_dir() else {
+            return Ok(None);
+        };
+        for dir in cwd.ancestors() {
+            let path = dir.join(FILE_NAME);
+            if path.is_file() {
+                // paths stay relative when the config is right here
+                let path = path.strip_prefix(&cwd).map_or(path.clone(), Into::into);
+                return ConfigFile::load(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+    pub fn load(path: &Path) -> Result<ConfigFile, Error> {
+        let table = read_table(path)?;
+        let mut values = vec![];
+        settings(&table, "", path, &mut values)?;
+        if let Some(key) = KEYS
+            .iter()
+            .find(|k| k.secret && values.iter().any(|(id, _)| *id == k.arg))
+        {
+            return Err(Error::Secret(path.to_owned(), key.name.to_owned()));
+        }
+        Ok(ConfigFile {
+            path: path.to_owned(),
+            profile: None,
+            values,
+        })
+    }
+    /// the `[name]` section of the profiles file, see [`profiles_path`]
+    pub fn profile(name: &str) -> Result<ConfigFile, Error> {
+        let path = profiles_path().ok_or(Error::NoConfigDir)?;
+        let table = read_table(&path)?;
+        let Some(toml::Value::Table(section)) = table.get(name) else {
+            return Err(Error::UnknownProfile(path, name.to_owned()));
+        };
+        let mut values = vec![];
+        settings(section, "", &path, &mut values)?;
+        Ok(ConfigFile {
+            path,
+            profile: Some(name.to_owned()),
+            values,
+        })
+    }
+    /// how the settings are referred to
+    pub fn source(&self) -> String {
+        match &self.profile {
+            Some(profile) => format!("profile {profile}"),
+            None => FILE_NAME.to_owned(),
+        }
+    }
+}
+/// `B3_PROFILES_FILE`, or `profiles.toml` in the beetree directory of
+/// `XDG_CONFIG_HOME` (`~/.config`)
+pub fn profiles_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("B3_PROFILES_FILE") {
+        return Some(path.into());
+    }
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("beetree").join(PROFILES_FILE_NAME))
+}
+fn read_table(path: &Path) -> Result<toml::Table, Error> {
+    let text = file_handling::read_from_file(path)?;
+    toml::from_str(&text).map_err(|e| Error::Parse(path.to_owned(), e))
+}
+/// the settings of `table`, `origin` is the file it was read from
+fn settings(
+    table: &toml::Table,
+    prefix: &str,
+    origin: &Path,
+    out: &mut Vec<(&'static str, String)>,
+) -> Result<(), Error> {
+    for (name, value) in table {
+        let name = format!("{prefix}{name}");
+        let key = KEYS.iter().find(|k| k.name == name);
+        match value {
+            toml::Value::Table(table) if key.is_some_and(|k| k.map) => {
+                let lines = table
+                    .iter()
+                    .map(|(name, value)| Some(format!("{name}: {}", scalar(value)?)))
+                    .collect::<Option<Vec<String>>>()
+                    .ok_or_else(|| Error::Type(origin.to_owned(), name.clone()))?;
+                out.push((key.expect("matched").arg, lines.join("\n")));
+                continue;
+            }
+            toml::Value::Table(table) => {
+                settings(table, &format!("{name}."), origin, out)?;
+                continue;
+            }
+            _ => {}
+        }
+        let key = key.ok_or_else(|| Error::UnknownKey(origin.to_owned(), name.clone()))?;
+        let values = match value {
+            toml::Value::Array(values) => values.iter().map(scalar).collect(),
+            value => scalar(value).map(|value| vec![value]),
+        };
+        let values = values.ok_or_else(|| Error::Type(origin.to_owned(), name.clone()))?;
+        if key.is_path {
+            // every path on its own, relative to the file
+            let dir = origin.parent().unwrap_or(Path::new(""));
+            for value in values {
+                out.push((key.arg, dir.join(value).to_string_lossy().into_owned()));
+            }
+        } else {
+            // other lists are given to the cli comma separated
+            out.push((key.arg, values.join(",")));
+        }
+    }
+    Ok(())
+}
+fn scalar(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Datetime(d) => Some(d.to_string()),
+        toml::Value::Array(_) | toml::Value::Table(_) => None,
+    }
+}
@@ -1,29 +1,154 @@
+use anyhow::Context;
+use beetree::audit::{self, AuditLog};
+use beetree::cassette::Cassette;
+use beetree::doctor;
+use beetree::file_handling;
+use beetree::format::{xliff, FileFormat};
 use beetree::lang;
 use beetree::lang::{Action, FindSpecified};
+use beetree::layout::Layout;
+use beetree::secret::{KeySource, Secret};
+use beetree::serializer::ValueSerializer;
 use beetree::translate;
+use beetree::usage::{Budget, Ledger, Meter, Prices};
+use clap::parser::ValueSource;
 use clap::ArgMatches;
 use std::path::PathBuf;
+use std::time::Duration;
 
 pub trait ToConfig<T> {
     type Error;
     fn to_config(&self) -> Result<T, Self::Error>;
 }
 
-impl ToConfig<translate::Config> for ArgMatches {
+/// the `--layout` template, the detected layouts when none is given
+fn parse_layout(args: &ArgMatches) -> anyhow::Result<Layout> {
+    let layout = args.get_one::<String>("layout").map(|s| s.parse());
+    Ok(layout.transpose()?.unwrap_or_default())
+}
+/// the `--serializer`, none to infer it from the file extension
+fn parse_serializer(args: &ArgMatches) -> anyhow::Result<Option<ValueSerializer>> {
+    let serializer = args.get_one::<String>("serializer").map(|s| s.parse());
+    Ok(serializer.transpose()?)
+}
+
+impl ToConfig<translate::Llm> for ArgMatches {
     type Error = anyhow::Error;
-    fn to_config(&self) -> Result<translate::Config, Self::Error> {
-        let api_key = self
-            .get_one::<String>("api_key")
-            .expect("default")
-            .to_string();
-        let model = self
-            .get_one::<String>("model")
-            .expect("required")
-            .to_string();
+    fn to_config(&self) -> Result<translate::Llm, Self::Error> {
+        // a key given on the cli wins, then the command, file and keyring
+        let source = if self.value_source("api_key") == Some(ValueSource::CommandLine) {
+            None
+        } else if let Some(command) = self.get_one::<String>("api_key_cmd") {
+            Some(KeySource::Command(command.to_owned()))
+        } else if let Some(file) = self.get_one::<PathBuf>("api_key_file") {
+            Some(KeySource::File(file.to_owned()))
+        } else {
+            self.get_one::<String>("api_key_keyring")
+                .map(|account| KeySource::Keyring(account.to_owned()))
+        };
+        let api_key = match source {
+            Some(source) => source.resolve()?,
+            None => Secret::new(self.get_one::<String>("api_key").expect("default")),
+        };
+        // only `models` goes without one
+        let model = self.get_one::<String>("model").cloned().unwrap_or_default();
         let host = self
             .get_one::<String>("host")
             .expect("required")
             .to_string();
+        let read = |id: &str| {
+            self.get_one::<PathBuf>(id)
+                .map(file_handling::read_from_file)
+                .transpose()
+        };
+        let prices = match self.get_one::<PathBuf>("prices") {
+            Some(path) => Prices::load(path)?,
+            None => Prices::default(),
+        };
+        let ledger = if self.get_flag("no_ledger") {
+            None
+        } else {
+            self.get_one::<PathBuf>("ledger")
+                .map(|path| Ledger { path: path.clone() })
+        };
+        let budget = Budget {
+            max_tokens: self.get_one::<u64>("max_tokens_total").copied(),
+            max_cost: self.get_one::<f64>("max_cost").copied(),
+        };
+        let auth = match self.get_one::<String>("auth").map(String::as_str) {
+            Some("header") => {
+                let name = self.get_one::<String>("auth_header").expect("default");
+                translate::Auth::Header(name.to_owned())
+            }
+            Some("none") => translate::Auth::None,
+            _ => translate::Auth::Bearer,
+        };
+        // the env and config file give them one per line
+        let headers = self
+            .get_many::<String>("headers")
+            .into_iter()
+            .flatten()
+            .flat_map(|value| value.lines())
+            .map(|header| {
+                let (name, value) = header
+                    .split_once(':')
+                    .with_context(|| format!("header {header:?} is not 'Name: value'"))?;
+                Ok((name.trim().to_owned(), Secret::new(value.trim())))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let seconds = |id: &str| self.get_one::<u64>(id).map(|s| Duration::from_secs(*s));
+        let http = translate::Http {
+            connect_timeout: seconds("connect_timeout"),
+            timeout: seconds("timeout"),
+            proxy: self.get_one::<String>("proxy").cloned(),
+            ca_certs: self
+                .get_many::<PathBuf>("ca_certs")
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+            insecure: self.get_flag("insecure"),
+        };
+        if http.insecure {
+            eprintln!("warning: tls certificates are not verified");
+        }
+        Ok(translate::Llm {
+            host,
+            api_key,
+            model,
+            endpoint: self
+                .get_one::<String>("endpoint")
+                .expect("default")
+                .to_owned(),
+            auth,
+            headers,
+            instructions: read("prompt_file")?,
+            glossary: read("glossary")?,
+            check_model: self.get_flag("check_model"),
+            sampling: translate::Sampling {
+                temperature: self.get_one::<f64>("temperature").copied(),
+                top_p: self.get_one::<f64>("top_p").copied(),
+                seed: self.get_one::<u64>("seed").copied(),
+                max_tokens: self.get_one::<u64>("max_tokens").copied(),
+            },
+            retry: translate::Retry {
+                attempts: *self.get_one::<u32>("retries").expect("default"),
+                ..translate::Retry::default()
+            },
+            meter: Meter::new(prices, ledger, budget),
+            stats: self.get_flag("stats"),
+            audit_log: self.get_one::<PathBuf>("audit_log").map(|path| AuditLog {
+                path: path.to_owned(),
+            }),
+            cassette: Cassette::from_env()?,
+            client: http.client()?,
+        })
+    }
+}
+impl ToConfig<translate::Config> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<translate::Config, Self::Error> {
+        let llm: translate::Llm = self.to_config()?;
         let output_file = self.get_one::<PathBuf>("output_file").cloned();
         let input = if let Some(text) = self.get_one::<String>("text") {
             beetree::Input::Text(text.to_string())
@@ -37,11 +162,14 @@ impl ToConfig<translate::Config> for ArgMatches {
             .get_one::<String>("languages")
             .expect("required")
             .to_string();
+        let fluent = self.get_flag("fluent")
+            || matches!(&input, beetree::Input::File(file)
+                if FileFormat::from_path(file) == FileFormat::Ftl);
         Ok(translate::Config {
-            api_key,
-            model,
+            llm,
             languages,
-            host,
+            stream: self.get_flag("stream"),
+            fluent,
             input,
             output_file,
             verbose,
@@ -98,7 +226,9 @@ impl ToConfig<lang::RemoveConfig> for ArgMatches {
             needle: destination_tag,
             file,
         };
+        let layout = parse_layout(self)?;
         Ok(lang::RemoveConfig {
+            layout,
             languages,
             base_path,
             dst_tag,
@@ -129,7 +259,9 @@ impl ToConfig<lang::FindConfig> for ArgMatches {
             needle: destination_tag,
             file,
         };
+        let layout = parse_layout(self)?;
         Ok(lang::FindConfig {
+            layout,
             languages,
             base_path,
             dst_tag,
@@ -158,8 +290,12 @@ impl ToConfig<lang::AppendConfig> for ArgMatches {
             let file = self.get_one::<PathBuf>("input_file").expect("required");
             beetree::Input::File(file.to_owned())
         };
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
         Ok(lang::AppendConfig {
+            layout,
             base_path,
+            serializer,
             src_tag,
             verbose: self.get_flag("verbose"),
             file,
@@ -195,8 +331,12 @@ impl ToConfig<lang::InsertConfig> for ArgMatches {
             needle: destination_tag,
             file,
         };
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
         Ok(lang::InsertConfig {
+            layout,
             base_path,
+            serializer,
             src_tag,
             dst_tag,
             verbose: self.get_flag("verbose"),
@@ -204,3 +344,249 @@ impl ToConfig<lang::InsertConfig> for ArgMatches {
         })
     }
 }
+impl ToConfig<lang::ReplaceConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::ReplaceConfig, Self::Error> {
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let input = if let Some(text) = self.get_one::<String>("text") {
+            beetree::Input::Text(text.to_string())
+        } else {
+            let file = self.get_one::<PathBuf>("input_file").expect("required");
+            beetree::Input::File(file.to_owned())
+        };
+        let destination_tag = self
+            .get_one::<String>("destination_tag")
+            .expect("required")
+            .to_owned();
+        let file = self
+            .get_one::<PathBuf>("search_file")
+            .map(ToOwned::to_owned);
+        let dst_tag = FindSpecified {
+            needle: destination_tag,
+            file,
+        };
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
+        Ok(lang::ReplaceConfig {
+            layout,
+            base_path,
+            dst_tag,
+            serializer,
+            verbose: self.get_flag("verbose"),
+            input,
+        })
+    }
+}
+impl ToConfig<lang::FmtConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::FmtConfig, Self::Error> {
+        let languages = self
+            .get_one::<String>("languages")
+            .expect("default")
+            .to_owned();
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let file = self
+            .get_one::<PathBuf>("search_file")
+            .map(ToOwned::to_owned);
+        let layout = parse_layout(self)?;
+        Ok(lang::FmtConfig {
+            layout,
+            languages,
+            base_path,
+            file,
+            check: self.get_flag("check"),
+            sections: self.get_flag("sections"),
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<lang::ExportXliffConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::ExportXliffConfig, Self::Error> {
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let source = self
+            .get_one::<String>("source")
+            .expect("required")
+            .to_owned();
+        let target = self
+            .get_one::<String>("target")
+            .expect("required")
+            .to_owned();
+        let version = match self
+            .get_one::<String>("xliff_version")
+            .expect("default")
+            .as_str()
+        {
+            "2.0" => xliff::Version::V2_0,
+            _ => xliff::Version::V1_2,
+        };
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
+        Ok(lang::ExportXliffConfig {
+            layout,
+            base_path,
+            source,
+            target,
+            version,
+            serializer,
+            file: self.get_one::<PathBuf>("search_file").cloned(),
+            output: self.get_one::<PathBuf>("output_file").cloned(),
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<lang::ImportXliffConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::ImportXliffConfig, Self::Error> {
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let input = self
+            .get_one::<PathBuf>("xliff")
+            .expect("required")
+            .to_owned();
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
+        Ok(lang::ImportXliffConfig {
+            layout,
+            base_path,
+            input,
+            serializer,
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<lang::ExportCsvConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::ExportCsvConfig, Self::Error> {
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let languages = self
+            .get_one::<String>("languages")
+            .expect("default")
+            .to_owned();
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
+        Ok(lang::ExportCsvConfig {
+            layout,
+            base_path,
+            languages,
+            serializer,
+            file: self.get_one::<PathBuf>("search_file").cloned(),
+            output: self.get_one::<PathBuf>("output_file").cloned(),
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<lang::ImportCsvConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::ImportCsvConfig, Self::Error> {
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let input = self.get_one::<PathBuf>("csv").expect("required").to_owned();
+        let serializer = parse_serializer(self)?;
+        let layout = parse_layout(self)?;
+        Ok(lang::ImportCsvConfig {
+            layout,
+            base_path,
+            input,
+            serializer,
+            yes: self.get_flag("yes"),
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<lang::PoFillConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<lang::PoFillConfig, Self::Error> {
+        let llm: translate::Llm = self.to_config()?;
+        let languages = self
+            .get_one::<String>("languages")
+            .expect("required")
+            .to_owned();
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let template = self
+            .get_one::<PathBuf>("template")
+            .expect("required")
+            .to_owned();
+        let layout = parse_layout(self)?;
+        Ok(lang::PoFillConfig {
+            layout,
+            llm,
+            languages,
+            base_path,
+            template,
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<translate::ModelsConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<translate::ModelsConfig, Self::Error> {
+        Ok(translate::ModelsConfig {
+            llm: self.to_config()?,
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<audit::ShowConfig> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<audit::ShowConfig, Self::Error> {
+        let path = self.get_one::<PathBuf>("audit_log").expect("required");
+        Ok(audit::ShowConfig {
+            log: AuditLog {
+                path: path.to_owned(),
+            },
+            since: self.get_one::<String>("since").cloned(),
+            until: self.get_one::<String>("until").cloned(),
+            key: self.get_one::<String>("key").cloned(),
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
+impl ToConfig<doctor::Config> for ArgMatches {
+    type Error = anyhow::Error;
+    fn to_config(&self) -> Result<doctor::Config, Self::Error> {
+        let base_path = self
+            .get_one::<PathBuf>("base_path")
+            .expect("default")
+            .to_owned();
+        let languages = self
+            .get_one::<String>("languages")
+            .expect("default")
+            .to_owned();
+        let layout = parse_layout(self)?;
+        let llm = if self.contains_id("host") && self.contains_id("model") {
+            let llm: Result<translate::Llm, anyhow::Error> = self.to_config();
+            llm.map_err(|e| e.to_string())
+        } else {
+            Err("no host or model set (--host, --model)".to_owned())
+        };
+        Ok(doctor::Config {
+            base_path,
+            languages,
+            layout,
+            llm,
+            config_file: None,
+            profile: None,
+            verbose: self.get_flag("verbose"),
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use crate::layout::Layout;
+use crate::{lang, translate};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("{0} check(s) failed")]
+    Failed(usize),
+}
+
+#[derive(Debug)]
+pub struct Config {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub languages: String,
+    /// the config file and profile the settings came from
+    pub config_file: Option<PathBuf>,
+    pub profile: Option<String>,
+    /// why there is no llm when it can't be set up
+    pub llm: Result<translate::Llm, String>,
+}
+
+struct Report {
+    failed: usize,
+}
+impl Report {
+    /// prints the outcome, true when it passed
+    fn check(&mut self, name: &str, result: Result<String, String>) -> bool {
+        match result {
+            Ok(msg) => {
+                println!("ok    {name}: {msg}");
+                true
+            }
+            Err(msg) => {
+                println!("FAIL  {name}: {msg}");
+                self.failed += 1;
+                false
+            }
+        }
+    }
+}
+
+/// checks the settings, the language files and the llm step by step
+pub fn run(config: Config) -> Result<(), Error> {
+    let mut report = Report { failed: 0 };
+    let languages: Vec<&str> = config.languages.split(',').collect();
+
+    let settings = match (&config.config_file, &config.profile) {
+        (Some(file), Some(profile)) => format!("{} with profile {profile}", file.display()),
+        (Some(file), None) => file.display().to_string(),
+        (None, Some(profile)) => format!("profile {profile}, no beetree.toml"),
+        (None, None) => "no beetree.toml, cli and env only".to_owned(),
+    };
+    report.check("config", Ok(settings));
+    report.check("layout", check_layout(&config, &languages));
+
+    let llm = match &config.llm {
+        Ok(llm) => llm,
+        Err(msg) => {
+            report.check("llm", Err(msg.clone()));
+            return Err(Error::Failed(report.failed));
+        }
+    };
+    let models = llm.models();
+    // without a connection there is nothing to translate with
+    let connected = !matches!(&models, Err(translate::Error::Reqwest(e)) if e.is_connect());
+    match models {
+        Ok(models) => {
+            let msg = format!("{} lists {} model(s)", llm.models_url(), models.len());
+            report.check("host", Ok(msg));
+            let listed = if models.iter().any(|m| m.id == llm.model) {
+                Ok(format!("{} is listed", llm.model))
+            } else {
+                Err(format!("{} is not listed by the host", llm.model))
+            };
+            report.check("model", listed);
+        }
+        Err(translate::Error::Status(401 | 403, _)) => {
+            report.check(
+                "host",
+                Err(format!("{} refused the api key", llm.models_url())),
+            );
+        }
+        Err(e) => {
+            report.check("host", Err(format!("{}: {e}", llm.models_url())));
+        }
+    }
+    if connected {
+        report.check(
+            "translate",
+            check_translation(llm, &config.languages, config.verbose),
+        );
+    }
+
+    if report.failed > 0 {
+        return Err(Error::Failed(report.failed));
+    }
+    Ok(())
+}
+/// every language has the same files
+fn check_layout(config: &Config, languages: &[&str]) -> Result<String, String> {
+    if !config.base_path.is_dir() {
+        return Err(format!("{} is not a directory", config.base_path.display()));
+    }
+    let files: Vec<(&str, Vec<PathBuf>)> = languages
+        .iter()
+        .map(|lang| {
+            let files = config.layout.files(&config.base_path, lang);
+            if config.verbose {
+                for (_, path) in &files {
+                    eprintln!("lang: {lang}, path: {}", path.display());
+                }
+            }
+            (*lang, files.into_iter().map(|(file, _)| file).collect())
+        })
+        .collect();
+    let mut all: Vec<&PathBuf> = files.iter().flat_map(|(_, f)| f).collect();
+    all.sort();
+    all.dedup();
+    let mut problems = vec![];
+    for (lang, lang_files) in &files {
+        if lang_files.is_empty() {
+            problems.push(format!("{lang} has no files"));
+            continue;
+        }
+        for file in &all {
+            if !lang_files.contains(file) {
+                problems.push(format!("{lang} misses {}", file.display()));
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems.join(", "));
+    }
+    Ok(format!(
+        "{} file(s) in each of {}",
+        all.len(),
+        languages.join(", ")
+    ))
+}
+/// a tiny translation through the prompt and the parser of the answer
+fn check_translation(
+    llm: &translate::Llm,
+    languages: &str,
+    verbose: bool,
+) -> Result<String, String> {
+    let answer = llm
+        .translate("Hello", languages, verbose)
+        .map_err(|e| e.to_string())?;
+    let translations = lang::gen_language_text(&answer)
+        .map_err(|_| format!("can't read the answer: {answer:?}"))?;
+    let missing: Vec<&str> = languages
+        .split(',')
+        .filter(|lang| lang::find_match(lang, &translations).is_none())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("no translation for {}", missing.join(", ")));
+    }
+    Ok(format!("got {languages}"))
+}
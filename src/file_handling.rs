@@ -1,4 +1,5 @@
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -24,16 +25,149 @@ where
     F: Fn(&Path) -> Option<T>,
 {
     let base = base.as_ref();
-    let res = if !base.is_dir() {
-        // let text = fs::read_to_string(base).ok()?;
-        predicate(&base)
+    if !base.is_dir() {
+        predicate(base)
     } else {
-        let res = base
-            .read_dir()
+        base.read_dir()
             .ok()?
             .flatten()
-            .find_map(|p| find_file(p.path(), predicate));
-        res
+            .find_map(|p| find_file(p.path(), predicate))
+    }
+}
+/// all files below `base` (or `base` itself when it is a file), sorted by path
+pub fn list_files(base: impl AsRef<Path>) -> Vec<PathBuf> {
+    let base = base.as_ref();
+    if !base.is_dir() {
+        return if base.is_file() {
+            vec![base.to_owned()]
+        } else {
+            vec![]
+        };
+    }
+    let mut files: Vec<PathBuf> = base
+        .read_dir()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(|p| list_files(p.path()))
+        .collect();
+    files.sort();
+    files
+}
+
+const BOM: char = '\u{feff}';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    /// as written by `genstrings` and older tools, recognized by its BOM or by
+    /// the zero bytes of ascii text
+    Utf16Le,
+    Utf16Be,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+/// the layout details of a text file that edits have to keep intact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextStyle {
+    pub encoding: Encoding,
+    pub bom: bool,
+    pub line_ending: LineEnding,
+    pub final_newline: bool,
+}
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            encoding: Encoding::Utf8,
+            bom: false,
+            line_ending: LineEnding::Lf,
+            final_newline: true,
+        }
+    }
+}
+impl TextStyle {
+    /// detects the style of `text` and returns it together with the text
+    /// stripped of its BOM and with `\n` line endings
+    pub fn normalize(text: &str) -> (TextStyle, String) {
+        let (bom, text) = match text.strip_prefix(BOM) {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let line_ending = match text.find('\n') {
+            Some(i) if text[..i].ends_with('\r') => LineEnding::CrLf,
+            _ => LineEnding::Lf,
+        };
+        let style = TextStyle {
+            encoding: Encoding::Utf8,
+            bom,
+            line_ending,
+            final_newline: text.is_empty() || text.ends_with('\n'),
+        };
+        (style, text.replace("\r\n", "\n"))
+    }
+    /// turns `\n` separated text back into the detected style
+    pub fn apply(&self, text: &str) -> String {
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let mut out = String::with_capacity(text.len() + 8);
+        if self.bom {
+            out.push(BOM);
+        }
+        let newline = match self.line_ending {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        };
+        out.push_str(&text.replace('\n', newline));
+        if self.final_newline && !text.is_empty() {
+            out.push_str(newline);
+        }
+        out
+    }
+}
+/// reads a file as `\n` separated text, see [`TextStyle::normalize`]
+pub fn read_text_file(path: impl AsRef<Path>) -> Result<(TextStyle, String), Error> {
+    let buf = path.as_ref().to_owned();
+    let bytes = fs::read(&path).map_err(|e| Error::Read(buf.clone(), e))?;
+    // utf-16 without a bom starts with an ascii character next to a zero byte
+    let even = bytes.len() % 2 == 0;
+    let (encoding, text) = match bytes.get(..2) {
+        Some([0xff, 0xfe]) => (Encoding::Utf16Le, decode_utf16(&bytes, u16::from_le_bytes)),
+        Some([0xfe, 0xff]) => (Encoding::Utf16Be, decode_utf16(&bytes, u16::from_be_bytes)),
+        Some([1..=0x7f, 0]) if even => {
+            (Encoding::Utf16Le, decode_utf16(&bytes, u16::from_le_bytes))
+        }
+        Some([0, 1..=0x7f]) if even => {
+            (Encoding::Utf16Be, decode_utf16(&bytes, u16::from_be_bytes))
+        }
+        _ => (Encoding::Utf8, String::from_utf8(bytes).ok()),
+    };
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid text",
+        )
     };
-    res
+    let text = text.ok_or_else(|| Error::Read(buf, invalid()))?;
+    let (style, text) = TextStyle::normalize(&text);
+    Ok((TextStyle { encoding, ..style }, text))
+}
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Option<String> {
+    let units = bytes
+        .chunks(2)
+        .map(|c| unit([c[0], *c.get(1).unwrap_or(&0)]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .ok()
+}
+pub fn write_text_file(path: impl AsRef<Path>, style: TextStyle, text: &str) -> Result<(), Error> {
+    let text = style.apply(text);
+    let bytes: Vec<u8> = match style.encoding {
+        Encoding::Utf8 => return write_to_file(path, &text),
+        Encoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        Encoding::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+    };
+    let buf = path.as_ref().to_owned();
+    fs::write(path, bytes).map_err(|e| Error::Write(buf, e))
 }
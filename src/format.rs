@@ -0,0 +1,140 @@
+use crate::serializer::ValueSerializer;
+use std::path::Path;
+use thiserror::Error;
+
+mod android;
+pub mod csv;
+mod ftl;
+mod json;
+pub(crate) mod line;
+pub mod po;
+mod properties;
+mod strings;
+mod xcstrings;
+pub mod xliff;
+pub mod xml;
+mod yaml;
+
+pub use android::AndroidFormat;
+pub use ftl::FtlFormat;
+pub use json::JsonFormat;
+pub use line::LineFormat;
+pub use po::PoFormat;
+pub use properties::PropertiesFormat;
+pub use strings::StringsFormat;
+pub use xcstrings::XcstringsFormat;
+pub use yaml::YamlFormat;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("key not found: {0}")]
+    KeyNotFound(String),
+    #[error("key already exists: {0}")]
+    KeyExists(String),
+    #[error("{0} can't be inserted before {1}, their parents differ")]
+    OtherParent(String, String),
+    #[error("parse error at line {line}: {msg}")]
+    Parse { line: usize, msg: String },
+}
+
+/// text level operations on a language file, keys are addressed as the format sees fit
+/// (exact keys for line files, dot paths for nested formats)
+pub trait LangFormat {
+    /// the line (0 based) where `key` is defined
+    fn find(&self, text: &str, key: &str) -> Option<usize>;
+    /// adds `key` right before the entry of `before`
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error>;
+    /// adds `key` at the end of the file (or of its parent)
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error>;
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error>;
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error>;
+    /// every key with its unescaped value, in file order
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// one `KEY=value` (or `key: value,`) per line
+    Line,
+    Json,
+    Yaml,
+    Properties,
+    Po,
+    Ftl,
+    /// android `strings.xml` resources
+    Android,
+    /// apple `.strings`
+    Strings,
+    /// apple string catalogs, one file for all languages
+    Xcstrings,
+}
+impl FileFormat {
+    /// `.xml` files are android resources only as `strings.xml` or below a `values*` dir
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let android = path.file_name().is_some_and(|n| n == "strings.xml")
+            || path
+                .parent()
+                .and_then(Path::file_name)
+                .is_some_and(|d| d.to_string_lossy().starts_with("values"));
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::Json,
+            Some("yml" | "yaml") => Self::Yaml,
+            Some("properties") => Self::Properties,
+            Some("po" | "pot") => Self::Po,
+            Some("ftl") => Self::Ftl,
+            Some("xml") if android => Self::Android,
+            Some("strings") => Self::Strings,
+            Some("xcstrings") => Self::Xcstrings,
+            _ => Self::Line,
+        }
+    }
+    /// `serializer` only matters for line files, other formats have their own quoting.
+    /// `lang` picks the language of a file that holds them all
+    pub fn lang_format(&self, lang: &str, serializer: ValueSerializer) -> Box<dyn LangFormat> {
+        match self {
+            Self::Line => Box::new(LineFormat { serializer }),
+            Self::Json => Box::new(JsonFormat),
+            Self::Yaml => Box::new(YamlFormat),
+            Self::Properties => Box::new(PropertiesFormat),
+            Self::Po => Box::new(PoFormat),
+            Self::Ftl => Box::new(FtlFormat),
+            Self::Android => Box::new(AndroidFormat),
+            Self::Strings => Box::new(StringsFormat),
+            Self::Xcstrings => Box::new(XcstringsFormat {
+                lang: lang.to_owned(),
+            }),
+        }
+    }
+    /// the text of a new file without entries, ready for `append`
+    pub fn empty_text(&self) -> &'static str {
+        match self {
+            Self::Json => "{}\n",
+            Self::Android => {
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n</resources>\n"
+            }
+            _ => "",
+        }
+    }
+}
+/// the format of `path` for `lang`, with the serializer chosen or inferred from the path
+pub fn for_path(
+    path: impl AsRef<Path>,
+    lang: &str,
+    serializer: Option<ValueSerializer>,
+) -> Box<dyn LangFormat> {
+    let path = path.as_ref();
+    FileFormat::from_path(path).lang_format(lang, ValueSerializer::resolve(serializer, path))
+}
+
+/// 0 based line number of the byte offset `pos`
+pub(crate) fn line_of(text: &str, pos: usize) -> usize {
+    text[..pos].matches('\n').count()
+}
+/// replaces `remove` lines at line `at` with `insert`
+pub(crate) fn splice(text: &str, at: usize, remove: usize, insert: Vec<String>) -> String {
+    let mut lines: Vec<String> = text.lines().map(ToOwned::to_owned).collect();
+    let at = at.min(lines.len());
+    lines.splice(at..at.saturating_add(remove).min(lines.len()), insert);
+    lines.join("\n")
+}
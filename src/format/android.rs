@@ -0,0 +1,362 @@
+use super::{line_of, xml, Error, LangFormat};
+use std::borrow::Cow;
+
+/// android `values-<lang>/strings.xml` resources. `<string>`s are addressed by
+/// name, the items of `<plurals>` as `name.quantity` and those of a
+/// `<string-array>` as `name.index`. replacing a whole `<plurals>` or
+/// `<string-array>` takes its items separated by `|`. inline markup like
+/// `<b>` or `<xliff:g>` is part of the value and kept as written.
+pub struct AndroidFormat;
+
+struct Resource<'a> {
+    key: String,
+    element: &'a xml::Element,
+    /// the `<plurals>` or `<string-array>` of an item
+    parent: Option<&'a xml::Element>,
+}
+impl Resource<'_> {
+    fn is_container(&self) -> bool {
+        self.element.name != "string" && self.parent.is_none()
+    }
+}
+
+fn resources(root: &xml::Element) -> Vec<Resource<'_>> {
+    let mut out = vec![];
+    for element in &root.children {
+        let Some(name) = element.attribute("name") else {
+            continue;
+        };
+        if !["string", "plurals", "string-array"].contains(&element.name.as_str()) {
+            continue;
+        }
+        out.push(Resource {
+            key: name.to_owned(),
+            element,
+            parent: None,
+        });
+        for (i, item) in element.children_named("item").enumerate() {
+            let sub = match element.name.as_str() {
+                "plurals" => item.attribute("quantity").unwrap_or_default().to_owned(),
+                _ => i.to_string(),
+            };
+            out.push(Resource {
+                key: format!("{name}.{sub}"),
+                element: item,
+                parent: Some(element),
+            });
+        }
+    }
+    out
+}
+fn find_resource<'a>(resources: &'a [Resource<'a>], key: &str) -> Result<&'a Resource<'a>, Error> {
+    resources
+        .iter()
+        .find(|r| r.key == key)
+        .ok_or_else(|| Error::KeyNotFound(key.to_owned()))
+}
+
+/// a piece of a resource value, inline markup like `<b>` or `<xliff:g>` is kept as written
+enum Part<'a> {
+    Text(Cow<'a, str>),
+    Markup(&'a str),
+}
+/// the length of the tag `s` starts with, `<` that opens no tag is text
+fn tag_len(s: &str) -> Option<usize> {
+    let name = s.strip_prefix('<')?;
+    let name = name.strip_prefix('/').unwrap_or(name);
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let end = s.find('>')?;
+    (!s[1..end].contains('<')).then_some(end + 1)
+}
+/// splits a value into its text and its markup
+fn split_markup(value: &str) -> Vec<Part<'_>> {
+    let mut parts = vec![];
+    let mut rest = value;
+    let mut text = 0;
+    while let Some(open) = rest[text..].find('<').map(|i| text + i) {
+        match tag_len(&rest[open..]) {
+            Some(len) => {
+                if open > 0 {
+                    parts.push(Part::Text(Cow::Borrowed(&rest[..open])));
+                }
+                parts.push(Part::Markup(&rest[open..open + len]));
+                rest = &rest[open + len..];
+                text = 0;
+            }
+            None => text = open + 1,
+        }
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(Cow::Borrowed(rest)));
+    }
+    parts
+}
+/// the parts of the content of `element`, its text xml unescaped
+fn content_parts<'a>(doc: &'a str, element: &xml::Element) -> Vec<Part<'a>> {
+    let mut parts = vec![];
+    let mut rest = &doc[element.inner.clone()];
+    while !rest.is_empty() {
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            parts.push(Part::Text(Cow::Borrowed(&cdata[..end])));
+            rest = cdata.get(end + 3..).unwrap_or("");
+        } else if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            parts.push(Part::Markup(&rest[..end]));
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            parts.push(Part::Text(Cow::Owned(xml::unescape(&rest[..end]))));
+            rest = &rest[end..];
+        }
+    }
+    parts
+}
+
+/// escapes for aapt, whitespace that would be collapsed is kept by quoting.
+/// markup is written as it is
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for part in split_markup(value) {
+        let text = match part {
+            Part::Markup(markup) => {
+                out.push_str(markup);
+                continue;
+            }
+            Part::Text(text) => text,
+        };
+        for c in text.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\'' => out.push_str("\\'"),
+                '"' => out.push_str("\\\""),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                '@' | '?' if out.is_empty() => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                c => out.push(c),
+            }
+        }
+    }
+    if value.starts_with(' ') || value.ends_with(' ') || value.contains("  ") {
+        format!("\"{out}\"")
+    } else {
+        out
+    }
+}
+/// the string aapt makes of the content of a resource, markup kept as written
+fn unescape(parts: Vec<Part<'_>>) -> String {
+    let mut out = String::new();
+    let mut quoted = false;
+    let mut space = false;
+    for part in parts {
+        let text = match part {
+            Part::Markup(markup) => {
+                if space && !out.is_empty() {
+                    out.push(' ');
+                }
+                space = false;
+                out.push_str(markup);
+                continue;
+            }
+            Part::Text(text) => text,
+        };
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() && !quoted {
+                space = true;
+                continue;
+            }
+            if space && !out.is_empty() {
+                out.push(' ');
+            }
+            space = false;
+            match c {
+                '"' => quoted = !quoted,
+                '\\' => match chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        out.extend(c);
+                    }
+                    Some(c) => out.push(c),
+                    None => {}
+                },
+                c => out.push(c),
+            }
+        }
+    }
+    out
+}
+fn string_text(name: &str, value: &str) -> String {
+    format!(
+        r#"<string name="{}">{}</string>"#,
+        xml::escape(name),
+        escape(value)
+    )
+}
+/// an `<item>` of `parent`, `sub` is the quantity for plurals
+fn item_text(parent: &xml::Element, sub: &str, value: &str) -> String {
+    match parent.name.as_str() {
+        "plurals" => format!(
+            r#"<item quantity="{}">{}</item>"#,
+            xml::escape(sub),
+            escape(value)
+        ),
+        _ => format!("<item>{}</item>", escape(value)),
+    }
+}
+/// whitespace at the start of the line holding `pos`
+fn indent_at(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+/// where `element` starts, a `<!-- comment -->` on the line right above included
+fn start_with_comment(text: &str, element: &xml::Element) -> usize {
+    let before = text[..element.start].trim_end_matches([' ', '\t']);
+    let Some(before) = before.strip_suffix('\n') else {
+        return element.start;
+    };
+    let before = before.trim_end();
+    match (before.ends_with("-->"), before.rfind("<!--")) {
+        (true, Some(comment)) if !text[comment..before.len()].contains('\n') => comment,
+        _ => element.start,
+    }
+}
+fn insert_before(text: &str, element: &xml::Element, entry: &str) -> String {
+    let start = start_with_comment(text, element);
+    let indent = indent_at(text, start);
+    let mut out = text.to_owned();
+    out.insert_str(start, &format!("{entry}\n{indent}"));
+    out
+}
+fn append_child(text: &str, parent: &xml::Element, entry: &str) -> String {
+    let mut out = text.to_owned();
+    match parent.children.last() {
+        Some(last) => {
+            let indent = indent_at(text, last.start);
+            out.insert_str(last.end, &format!("\n{indent}{entry}"));
+        }
+        None => {
+            let outer = indent_at(text, parent.start);
+            out.replace_range(
+                parent.inner.clone(),
+                &format!("\n{outer}    {entry}\n{outer}"),
+            );
+        }
+    }
+    out
+}
+/// removes `element` and its comment, with their lines when they are alone on them
+fn remove_element(text: &str, element: &xml::Element) -> String {
+    let start = start_with_comment(text, element);
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[element.end..]
+        .find('\n')
+        .map_or(text.len(), |i| element.end + i + 1);
+    let alone =
+        text[line_start..start].trim().is_empty() && text[element.end..line_end].trim().is_empty();
+    let mut out = text.to_owned();
+    if alone {
+        out.replace_range(line_start..line_end, "");
+    } else {
+        out.replace_range(start..element.end, "");
+    }
+    out
+}
+
+impl LangFormat for AndroidFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        let root = xml::parse(text).ok()?;
+        let resources = resources(&root);
+        let resource = find_resource(&resources, key).ok()?;
+        Some(line_of(text, resource.element.start))
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let root = xml::parse(text)?;
+        let resources = resources(&root);
+        if find_resource(&resources, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let target = find_resource(&resources, before)?;
+        // an item of the same parent goes right before the target item
+        let parent_name = target.parent.and_then(|p| p.attribute("name"));
+        let sub = parent_name.and_then(|p| key.strip_prefix(p)?.strip_prefix('.'));
+        let out = match (target.parent, sub) {
+            (Some(parent), Some(sub)) => {
+                insert_before(text, target.element, &item_text(parent, sub, value))
+            }
+            (Some(parent), None) => insert_before(text, parent, &string_text(key, value)),
+            (None, _) => insert_before(text, target.element, &string_text(key, value)),
+        };
+        Ok(out)
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let root = xml::parse(text)?;
+        let resources = resources(&root);
+        if find_resource(&resources, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        // a new item of an existing `<plurals>` or `<string-array>`
+        let container = key.rsplit_once('.').and_then(|(name, sub)| {
+            let parent = resources
+                .iter()
+                .find(|r| r.key == name && r.is_container())?;
+            Some((parent.element, sub))
+        });
+        let out = match container {
+            Some((parent, sub)) => append_child(text, parent, &item_text(parent, sub, value)),
+            None => append_child(text, &root, &string_text(key, value)),
+        };
+        Ok(out)
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let root = xml::parse(text)?;
+        let resources = resources(&root);
+        let target = find_resource(&resources, key)?;
+        Ok(remove_element(text, target.element))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let root = xml::parse(text)?;
+        let resources = resources(&root);
+        let target = find_resource(&resources, key)?;
+        let mut out = text.to_owned();
+        if !target.is_container() {
+            out.replace_range(target.element.inner.clone(), &escape(value));
+            return Ok(out);
+        }
+        // back to front so the positions stay valid
+        let forms: Vec<&str> = value.split('|').collect();
+        let items: Vec<&xml::Element> = target.element.children_named("item").collect();
+        for (i, item) in items.iter().enumerate().rev() {
+            let form = forms.get(i).or(forms.last()).expect("split yields a form");
+            out.replace_range(item.inner.clone(), &escape(form));
+        }
+        Ok(out)
+    }
+    /// strings marked `translatable="false"` are left out
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let root = xml::parse(text)?;
+        let entries = resources(&root)
+            .into_iter()
+            .filter(|r| !r.is_container())
+            .filter(|r| {
+                let element = r.parent.unwrap_or(r.element);
+                element.attribute("translatable") != Some("false")
+            })
+            .map(|r| (r.key, unescape(content_parts(text, r.element))));
+        Ok(entries.collect())
+    }
+}
@@ -0,0 +1,101 @@
+use super::Error;
+use std::borrow::Cow;
+
+/// the first characters spreadsheets take a cell for a formula with
+const FORMULA_START: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// whether `field` starts like a formula once its leading `'`s are left out
+fn is_guarded(field: &str) -> bool {
+    field.trim_start_matches('\'').starts_with(FORMULA_START)
+}
+/// prefixes fields a spreadsheet would run as a formula with `'`, which makes them text.
+/// fields that already have `'`s in front of one get another so [`unguard`] gives them back
+fn guard(field: &str) -> Cow<'_, str> {
+    if is_guarded(field) {
+        Cow::Owned(format!("'{field}"))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+/// drops the `'` [`guard`] put in front of a formula
+fn unguard(field: String) -> String {
+    match field.strip_prefix('\'') {
+        Some(rest) if is_guarded(rest) => rest.to_owned(),
+        _ => field,
+    }
+}
+/// rfc 4180 fields, quoted only when needed. fields starting like a formula
+/// (`=` `+` `-` `@`) get a `'` in front so spreadsheets show them as text
+pub fn write_row<S: AsRef<str>>(fields: &[S], delimiter: char) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            let field = guard(field.as_ref());
+            let quoted = field.contains([delimiter, '"', '\n', '\r'])
+                || field.starts_with(' ')
+                || field.ends_with(' ');
+            if quoted {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.into_owned()
+            }
+        })
+        .collect();
+    fields.join(&delimiter.to_string())
+}
+/// the delimiter spreadsheets saved the header with, `,` `;` or tab
+pub fn delimiter(text: &str) -> char {
+    let header = text.lines().next().unwrap_or_default();
+    header
+        .chars()
+        .find(|c| [',', ';', '\t'].contains(c))
+        .unwrap_or(',')
+}
+/// all rows of `text`, quoted fields may span lines. the `'` in front of
+/// formulas is dropped again
+pub fn parse(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, Error> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                let start = line;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            line += usize::from(c == '\n');
+                            field.push(c);
+                        }
+                        None => {
+                            return Err(Error::Parse {
+                                line: start,
+                                msg: "unterminated quoted field".to_owned(),
+                            })
+                        }
+                    }
+                }
+            }
+            c if c == delimiter => row.push(unguard(std::mem::take(&mut field))),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                row.push(unguard(std::mem::take(&mut field)));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(unguard(field));
+        rows.push(row);
+    }
+    Ok(rows)
+}
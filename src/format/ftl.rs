@@ -0,0 +1,331 @@
+use super::{splice, Error, LangFormat};
+
+/// project fluent `.ftl` files. messages and terms (`-brand`) are addressed by id,
+/// their attributes by `id.attribute`
+pub struct FtlFormat;
+
+#[derive(Debug)]
+struct Span {
+    /// first line, attached comments included
+    start: usize,
+    /// the line with the `=`
+    line: usize,
+    /// one past the last line of the value (attributes excluded for messages)
+    value_end: usize,
+}
+#[derive(Debug)]
+struct Message {
+    id: String,
+    span: Span,
+    /// one past the last line, attributes included
+    end: usize,
+    attributes: Vec<(String, Span)>,
+}
+
+fn is_identifier(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    s.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+/// the id of an `id = ...` line
+fn entry_id(line: &str) -> Option<&str> {
+    let (id, _) = line.split_once('=')?;
+    let id = id.trim_end();
+    (is_identifier(id) && !line.starts_with(char::is_whitespace)).then_some(id)
+}
+/// the name of an indented `.attribute = ...` line
+fn attribute_name(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if trimmed.len() == line.len() {
+        return None;
+    }
+    let (name, _) = trimmed.strip_prefix('.')?.split_once('=')?;
+    let name = name.trim_end();
+    is_identifier(name).then_some(name)
+}
+fn parse(text: &str) -> Vec<Message> {
+    let lines: Vec<&str> = text.lines().collect();
+    // indented lines, and the `}` / variants of a select, continue the entry
+    let continues = |line: &str| line.trim().is_empty() || line.starts_with([' ', '}', '[', '*']);
+    // one past the last non blank line of the block from `from` on
+    let block_end = |from: usize| {
+        let mut end = from;
+        let mut i = from;
+        while i < lines.len() && continues(lines[i]) {
+            if !lines[i].trim().is_empty() {
+                end = i + 1;
+            }
+            i += 1;
+        }
+        end
+    };
+    let mut messages: Vec<Message> = vec![];
+    let mut comment_start = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let Some(id) = entry_id(line) else {
+            // a `#` comment directly above a message belongs to it
+            comment_start = match line {
+                l if l.starts_with("# ") || l == "#" => comment_start.or(Some(i)),
+                _ => None,
+            };
+            i += 1;
+            continue;
+        };
+        let end = block_end(i + 1);
+        let mut attributes: Vec<(String, Span)> = vec![];
+        let mut value_end = end;
+        for (a, line) in lines.iter().enumerate().take(end).skip(i + 1) {
+            let Some(name) = attribute_name(line) else {
+                continue;
+            };
+            value_end = value_end.min(a);
+            if let Some((_, span)) = attributes.last_mut() {
+                span.value_end = a;
+            }
+            attributes.push((
+                name.to_owned(),
+                Span {
+                    start: a,
+                    line: a,
+                    value_end: end,
+                },
+            ));
+        }
+        // blank lines between the value and the first attribute stay outside
+        while value_end > i + 1 && lines[value_end - 1].trim().is_empty() {
+            value_end -= 1;
+        }
+        messages.push(Message {
+            id: id.to_owned(),
+            span: Span {
+                start: comment_start.take().unwrap_or(i),
+                line: i,
+                value_end,
+            },
+            end,
+            attributes,
+        });
+        i = end;
+    }
+    messages
+}
+/// the span for `id` or `id.attribute`
+fn find_span<'a>(messages: &'a [Message], key: &str) -> Result<(&'a Message, &'a Span), Error> {
+    let not_found = || Error::KeyNotFound(key.to_owned());
+    if let Some(message) = messages.iter().find(|m| m.id == key) {
+        return Ok((message, &message.span));
+    }
+    let (id, name) = key.rsplit_once('.').ok_or_else(not_found)?;
+    let message = messages.iter().find(|m| m.id == id).ok_or_else(not_found)?;
+    let (_, span) = message
+        .attributes
+        .iter()
+        .find(|(n, _)| n == name)
+        .ok_or_else(not_found)?;
+    Ok((message, span))
+}
+
+/// puts the variants of select expressions on their own lines, fluent requires it
+fn layout_selects(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut out = String::with_capacity(value.len());
+    // one entry per open placeable, for a select expression whether its
+    // variants are on lines of their own already
+    let mut stack: Vec<Option<bool>> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        let select = stack.last().copied().flatten();
+        match c {
+            '{' => {
+                stack.push(None);
+                out.push(c);
+            }
+            '-' if chars.get(i) == Some(&'>') && stack.last() == Some(&None) => {
+                i += 1;
+                out.push_str("->");
+                let blank = chars[i..].iter().take_while(|c| c.is_whitespace());
+                let over_lines = blank.clone().any(|c| *c == '\n');
+                i += blank.count();
+                *stack.last_mut().expect("checked") = Some(over_lines);
+            }
+            '[' | '*'
+                if select.is_some_and(|over_lines| is_variant_key(&chars, i - 1, over_lines)) =>
+            {
+                let indent = " ".repeat(4 * stack.len() - usize::from(c == '*'));
+                out.truncate(out.trim_end().len());
+                out.push('\n');
+                out.push_str(&indent);
+                out.push(c);
+            }
+            '}' if select.is_some() => {
+                stack.pop();
+                out.truncate(out.trim_end().len());
+                out.push('\n');
+                out.push_str(&" ".repeat(4 * stack.len()));
+                out.push(c);
+            }
+            '}' => {
+                stack.pop();
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+/// whether the `[key]` or `*[key]` of a variant starts at `at`: first on its
+/// line in selects laid out over lines, else after `->` or a blank. the key is
+/// an identifier or a number
+fn is_variant_key(chars: &[char], at: usize, over_lines: bool) -> bool {
+    let before = chars[..at].iter().rev().find(|c| !matches!(c, ' ' | '\t'));
+    let starts = match before {
+        Some('\n' | '>') => true,
+        Some(_) => !over_lines && at > 0 && chars[at - 1].is_whitespace(),
+        None => false,
+    };
+    let rest = &chars[at + usize::from(chars[at] == '*')..];
+    if !starts || rest.first() != Some(&'[') {
+        return false;
+    }
+    let Some(close) = rest.iter().position(|c| *c == ']') else {
+        return false;
+    };
+    let key: String = rest[1..close].iter().collect();
+    let key = key.trim();
+    let is_identifier = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+    let number = key.strip_prefix('-').unwrap_or(key);
+    let is_number = number.split('.').count() <= 2
+        && number
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    is_identifier || is_number
+}
+/// `head = value` with multi line values indented below the head
+fn entry_lines(head: &str, value: &str, indent: &str) -> Vec<String> {
+    let value = layout_selects(value);
+    if !value.contains('\n') {
+        return vec![format!("{head} = {value}")];
+    }
+    let mut lines = vec![format!("{head} =")];
+    lines.extend(value.lines().map(|l| format!("{indent}    {l}")));
+    lines
+}
+/// the pattern of a span, multi line patterns without their common indentation
+fn pattern(lines: &[&str], span: &Span) -> String {
+    let (_, first) = lines[span.line]
+        .split_once('=')
+        .expect("entry lines have a =");
+    let rest = &lines[span.line + 1..span.value_end];
+    let dedent = rest
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut pattern: Vec<&str> = vec![first.trim()];
+    pattern.extend(
+        rest.iter()
+            .map(|l| l.get(dedent..).unwrap_or("").trim_end()),
+    );
+    if pattern[0].is_empty() {
+        pattern.remove(0);
+    }
+    pattern.join("\n")
+}
+
+impl LangFormat for FtlFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        let messages = parse(text);
+        find_span(&messages, key).ok().map(|(_, span)| span.line)
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let messages = parse(text);
+        if find_span(&messages, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let (target, _) = find_span(&messages, before)?;
+        let mut lines = entry_lines(key, value, "");
+        // keep the blank line between messages when the file uses them
+        let start = target.span.start;
+        if start > 0
+            && text
+                .lines()
+                .nth(start - 1)
+                .is_some_and(|l| l.trim().is_empty())
+        {
+            lines.push(String::new());
+        }
+        Ok(splice(text, start, 0, lines))
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let messages = parse(text);
+        if find_span(&messages, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        // an attribute of an existing message goes at the end of that message
+        let parent = key
+            .rsplit_once('.')
+            .and_then(|(id, name)| Some((messages.iter().find(|m| m.id == id)?, name)));
+        if let Some((message, name)) = parent {
+            let lines = entry_lines(&format!("    .{name}"), value, "    ");
+            return Ok(splice(text, message.end, 0, lines));
+        }
+        let mut lines = entry_lines(key, value, "");
+        if !text.trim().is_empty() {
+            lines.insert(0, String::new());
+        }
+        let end = text.trim_end().lines().count();
+        Ok(splice(text, end, usize::MAX, lines))
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let messages = parse(text);
+        let (message, span) = find_span(&messages, key)?;
+        if std::ptr::eq(span, &message.span) {
+            // take the blank line that separates it from the next message along
+            let lines: Vec<&str> = text.lines().collect();
+            let mut end = message.end;
+            while end < lines.len() && lines[end].trim().is_empty() {
+                end += 1;
+            }
+            return Ok(splice(text, span.start, end - span.start, vec![]));
+        }
+        Ok(splice(
+            text,
+            span.start,
+            span.value_end - span.start,
+            vec![],
+        ))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let messages = parse(text);
+        let (_, span) = find_span(&messages, key)?;
+        let line = text.lines().nth(span.line).expect("span line exists");
+        let (head, _) = line.split_once('=').expect("entry lines have a =");
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let lines = entry_lines(head.trim_end(), value, indent);
+        Ok(splice(text, span.line, span.value_end - span.line, lines))
+    }
+    /// attributes are listed as `id.attribute`, messages without a value are left out
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut out = vec![];
+        for message in parse(text) {
+            let value = pattern(&lines, &message.span);
+            if !value.is_empty() {
+                out.push((message.id.clone(), value));
+            }
+            for (name, span) in &message.attributes {
+                out.push((format!("{}.{name}", message.id), pattern(&lines, span)));
+            }
+        }
+        Ok(out)
+    }
+}
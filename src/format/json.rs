@@ -0,0 +1,345 @@
+use super::{line_of, Error, LangFormat};
+
+/// json objects, flat (`"home.title"`) or nested (`home: { title }`) keys are
+/// both addressed with dot paths. edits splice the original text so indentation
+/// and key order stay as they were.
+pub struct JsonFormat;
+
+#[derive(Debug)]
+struct Object {
+    /// position of `{`
+    start: usize,
+    /// position of `}`
+    end: usize,
+    members: Vec<Member>,
+}
+#[derive(Debug)]
+struct Member {
+    key: String,
+    /// position of the opening quote of the key
+    start: usize,
+    value_start: usize,
+    value_end: usize,
+    object: Option<Object>,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> Error {
+        Error::Parse {
+            line: line_of(self.text, self.pos.min(self.text.len())) + 1,
+            msg: msg.to_owned(),
+        }
+    }
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+    fn expect(&mut self, b: u8) -> Result<(), Error> {
+        self.skip_ws();
+        if self.peek() != Some(b) {
+            return Err(self.error(&format!("expected '{}'", b as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+    fn string(&mut self) -> Result<String, Error> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => break,
+                Some(_) => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        serde_json::from_str(&self.text[start..self.pos]).map_err(|e| self.error(&e.to_string()))
+    }
+    /// parses any value, returns the object when it is one
+    fn value(&mut self) -> Result<Option<Object>, Error> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'{') => self.object().map(Some),
+            Some(b'[') => {
+                self.pos += 1;
+                self.skip_ws();
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(None);
+                }
+                loop {
+                    self.value()?;
+                    self.skip_ws();
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {
+                            self.pos += 1;
+                            return Ok(None);
+                        }
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some(b'"') => self.string().map(|_| None),
+            Some(_) => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || b"+-.".contains(&b))
+                {
+                    self.pos += 1;
+                }
+                if start == self.pos {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(None)
+            }
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+    fn object(&mut self) -> Result<Object, Error> {
+        self.skip_ws();
+        let start = self.pos;
+        self.expect(b'{')?;
+        let mut members = vec![];
+        self.skip_ws();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Object {
+                start,
+                end: self.pos - 1,
+                members,
+            });
+        }
+        loop {
+            self.skip_ws();
+            let member_start = self.pos;
+            let key = self.string()?;
+            self.expect(b':')?;
+            self.skip_ws();
+            let value_start = self.pos;
+            let object = self.value()?;
+            members.push(Member {
+                key,
+                start: member_start,
+                value_start,
+                value_end: self.pos,
+                object,
+            });
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Object {
+                        start,
+                        end: self.pos - 1,
+                        members,
+                    });
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+fn parse(text: &str) -> Result<Object, Error> {
+    let mut parser = Parser { text, pos: 0 };
+    let root = parser.object()?;
+    parser.skip_ws();
+    if parser.pos != text.len() {
+        return Err(parser.error("trailing characters after the root object"));
+    }
+    Ok(root)
+}
+
+/// the member for `path` together with the object holding it
+fn lookup<'a>(object: &'a Object, path: &str) -> Option<(&'a Object, &'a Member)> {
+    object.members.iter().find_map(|m| {
+        if m.key == path {
+            Some((object, m))
+        } else {
+            let rest = path.strip_prefix(&m.key)?.strip_prefix('.')?;
+            lookup(m.object.as_ref()?, rest)
+        }
+    })
+}
+/// the deepest existing object on `path` and the part of the path still missing
+fn deepest<'a, 'p>(object: &'a Object, path: &'p str) -> (&'a Object, &'p str) {
+    object
+        .members
+        .iter()
+        .find_map(|m| {
+            let rest = path.strip_prefix(&m.key)?.strip_prefix('.')?;
+            Some(deepest(m.object.as_ref()?, rest))
+        })
+        .unwrap_or((object, path))
+}
+
+/// whitespace at the start of the line holding `pos`
+fn indent_at(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+/// the indentation step used in the file, two spaces when it can't be told
+fn indent_unit(text: &str, root: &Object) -> String {
+    root.members
+        .first()
+        .map(|m| indent_at(text, m.start))
+        .filter(|i| !i.is_empty() && text[root.start..root.members[0].start].contains('\n'))
+        .unwrap_or("  ")
+        .to_owned()
+}
+/// whitespace that follows the commas between members of `object`
+fn separator(text: &str, object: &Object) -> String {
+    match &object.members[..] {
+        [first, second, ..] => {
+            let between = &text[first.value_end..second.start];
+            let comma = between.find(',').expect("members are separated");
+            between[comma + 1..].to_owned()
+        }
+        [first] if text[object.start + 1..first.start].contains('\n') => {
+            format!("\n{}", indent_at(text, first.start))
+        }
+        _ => " ".to_owned(),
+    }
+}
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).expect("strings serialize")
+}
+/// `"key": value` where dots in `key` become nested objects
+fn member_text(key: &str, value: &str, indent: &str, unit: &str) -> String {
+    match key.split_once('.') {
+        None => format!("{}: {}", quote(key), quote(value)),
+        Some((head, rest)) => {
+            let inner = format!("{indent}{unit}");
+            format!(
+                "{}: {{\n{inner}{}\n{indent}}}",
+                quote(head),
+                member_text(rest, value, &inner, unit)
+            )
+        }
+    }
+}
+/// the string members below `object` with their dot paths
+fn collect(text: &str, object: &Object, prefix: &str, out: &mut Vec<(String, String)>) {
+    for member in &object.members {
+        let key = format!("{prefix}{}", member.key);
+        let value = &text[member.value_start..member.value_end];
+        match &member.object {
+            Some(object) => collect(text, object, &format!("{key}."), out),
+            None if value.starts_with('"') => out.push((
+                key,
+                serde_json::from_str(value).expect("parsed strings are valid"),
+            )),
+            None => {}
+        }
+    }
+}
+
+impl LangFormat for JsonFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        let root = parse(text).ok()?;
+        lookup(&root, key).map(|(_, m)| line_of(text, m.start))
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let root = parse(text)?;
+        let (parent, target) =
+            lookup(&root, before).ok_or_else(|| Error::KeyNotFound(before.to_owned()))?;
+        // the new key lives next to `before`, it drops the path of their parent
+        let parent_path = &before[..before.len() - target.key.len()];
+        let name = key
+            .strip_prefix(parent_path)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| Error::OtherParent(key.to_owned(), before.to_owned()))?;
+        if parent.members.iter().any(|m| m.key == name) {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let entry = format!(
+            "{}: {},{}",
+            quote(name),
+            quote(value),
+            separator(text, parent)
+        );
+        let mut out = text.to_owned();
+        out.insert_str(target.start, &entry);
+        Ok(out)
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let root = parse(text)?;
+        if lookup(&root, key).is_some() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let unit = indent_unit(text, &root);
+        let (parent, missing) = deepest(&root, key);
+        // flat files keep their dotted keys flat
+        let flat = parent.members.iter().any(|m| m.key.contains('.'));
+        let mut out = text.to_owned();
+        match parent.members.last() {
+            Some(last) => {
+                let indent = indent_at(text, last.start);
+                let member = if flat {
+                    format!("{}: {}", quote(missing), quote(value))
+                } else {
+                    member_text(missing, value, indent, &unit)
+                };
+                let separator = separator(text, parent);
+                out.insert_str(last.value_end, &format!(",{separator}{member}"));
+            }
+            None => {
+                let outer = indent_at(text, parent.start);
+                let indent = format!("{outer}{unit}");
+                let member = if flat {
+                    format!("{}: {}", quote(missing), quote(value))
+                } else {
+                    member_text(missing, value, &indent, &unit)
+                };
+                out.replace_range(
+                    parent.start + 1..parent.end,
+                    &format!("\n{indent}{member}\n{outer}"),
+                );
+            }
+        }
+        Ok(out)
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let root = parse(text)?;
+        let (parent, target) =
+            lookup(&root, key).ok_or_else(|| Error::KeyNotFound(key.to_owned()))?;
+        let index = parent
+            .members
+            .iter()
+            .position(|m| m.start == target.start)
+            .expect("target is a member of parent");
+        let range = match (parent.members.get(index + 1), index) {
+            (Some(next), _) => target.start..next.start,
+            (None, 0) => parent.start + 1..parent.end,
+            (None, i) => parent.members[i - 1].value_end..target.value_end,
+        };
+        let mut out = text.to_owned();
+        out.replace_range(range, "");
+        Ok(out)
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let root = parse(text)?;
+        let (_, target) = lookup(&root, key).ok_or_else(|| Error::KeyNotFound(key.to_owned()))?;
+        let mut out = text.to_owned();
+        out.replace_range(target.value_start..target.value_end, &quote(value));
+        Ok(out)
+    }
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let root = parse(text)?;
+        let mut out = vec![];
+        collect(text, &root, "", &mut out);
+        Ok(out)
+    }
+}
@@ -0,0 +1,197 @@
+use super::{Error, LangFormat};
+use crate::serializer::ValueSerializer;
+
+/// files with one entry per line, keys are matched exactly once `export` and
+/// quotes are stripped
+pub struct LineFormat {
+    pub serializer: ValueSerializer,
+}
+/// the key and the raw value of an entry line
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.starts_with(['#', '/', '*']) {
+        return None;
+    }
+    let (key, value) = line.split_once(['=', ':'])?;
+    let key = key
+        .trim()
+        .trim_start_matches("export ")
+        .trim_matches(['"', '\'']);
+    // `const messages = {` and the like are no entries
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((key, value))
+}
+/// the byte range of the value of an entry line: what follows the separator,
+/// without the surrounding whitespace and the trailing `,` of Js and Json
+fn value_span(line: &str, serializer: ValueSerializer) -> (usize, usize) {
+    let Some(separator) = line.find(['=', ':']) else {
+        return (line.len(), line.len());
+    };
+    let rest = &line[separator + 1..];
+    let start = line.len() - rest.trim_start().len();
+    let value = &line[start..];
+    let quoted = value
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\''))
+        .and_then(|quote| closing_quote(&value[1..], quote));
+    if let Some(close) = quoted {
+        return (start, start + close + 2);
+    }
+    let value = value.trim_end();
+    let value = match serializer {
+        ValueSerializer::Js | ValueSerializer::Json => value.strip_suffix(',').unwrap_or(value),
+        _ => value,
+    };
+    (start, start + value.trim_end().len())
+}
+impl LineFormat {
+    fn find_or_err(&self, text: &str, key: &str) -> Result<usize, Error> {
+        self.find(text, key)
+            .ok_or_else(|| Error::KeyNotFound(key.to_owned()))
+    }
+}
+impl LangFormat for LineFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        text.lines().enumerate().find_map(|(i, line)| {
+            split_entry(line)
+                .is_some_and(|(k, _)| k == key)
+                .then_some(i)
+        })
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let index = self.find_or_err(text, before)?;
+        let mut lines: Vec<&str> = text.lines().collect();
+        // indented like the entry it goes in front of
+        let target = lines[index];
+        let indent = &target[..target.len() - target.trim_start().len()];
+        let entry = format!("{indent}{}", self.serializer.entry(key, value));
+        lines.insert(index, &entry);
+        Ok(lines.join("\n"))
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let inter = if text.is_empty() || text.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        Ok(text.to_owned() + inter + &self.serializer.entry(key, value))
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let index = self.find_or_err(text, key)?;
+        let lines: Vec<&str> = text
+            .lines()
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .map(|(_, l)| l)
+            .collect();
+        Ok(lines.join("\n"))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let index = self.find_or_err(text, key)?;
+        let lines: Vec<String> = text
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                if i != index {
+                    return l.to_owned();
+                }
+                // keep the line as written, only the value is replaced
+                let (start, end) = value_span(l, self.serializer);
+                format!(
+                    "{}{}{}",
+                    &l[..start],
+                    self.serializer.quote(value),
+                    &l[end..]
+                )
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let entries = text.lines().filter_map(|line| {
+            let (key, value) = split_entry(line)?;
+            let value = value.trim();
+            let value = value.strip_suffix(',').unwrap_or(value);
+            Some((key.to_owned(), self.serializer.unquote(value)))
+        });
+        Ok(entries.collect())
+    }
+}
+
+/// a line of a line file as [`parse`] sees it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Line<'a> {
+    Entry {
+        /// the key as written, trimmed
+        key: &'a str,
+        /// the value as written, trimmed, quotes and inner newlines included
+        value: String,
+        /// the line (0 based) the entry starts on
+        line: usize,
+        /// how many lines the entry spans
+        lines: usize,
+    },
+    /// comments, blank lines and anything else that is no entry
+    Other(&'a str),
+}
+/// splits `text` into entries and other lines. a value opened by a quote that
+/// isn't closed on its own line goes on until the line that closes it
+pub(crate) fn parse<'a>(text: &'a str, separators: &[char]) -> Result<Vec<Line<'a>>, Error> {
+    let mut parsed = vec![];
+    let mut lines = text.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        let trimmed = line.trim();
+        let split = line
+            .split_once(separators)
+            .filter(|_| !trimmed.starts_with(['#', '/', '*']));
+        let Some((key, value)) = split.filter(|(k, _)| {
+            let k = k.trim();
+            !k.is_empty()
+                && !k
+                    .trim_start_matches("export ")
+                    .contains(char::is_whitespace)
+        }) else {
+            parsed.push(Line::Other(line));
+            continue;
+        };
+        let mut value = value.trim().to_owned();
+        let mut spans = 1;
+        if let Some(quote) = value.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+            while closing_quote(&value[1..], quote).is_none() {
+                let Some((_, next)) = lines.next() else {
+                    return Err(Error::Parse {
+                        line: i + 1,
+                        msg: format!("no closing {quote} for the value of {}", key.trim()),
+                    });
+                };
+                value.push('\n');
+                value.push_str(next.trim_end());
+                spans += 1;
+            }
+        }
+        parsed.push(Line::Entry {
+            key: key.trim(),
+            value,
+            line: i,
+            lines: spans,
+        });
+    }
+    Ok(parsed)
+}
+/// the byte offset of the first unescaped `quote` in `text`
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            c if c == quote => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
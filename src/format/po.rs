@@ -0,0 +1,434 @@
+use super::{splice, Error, LangFormat};
+
+/// gettext `.po` / `.pot` files. entries are addressed by their msgid, or by
+/// `msgctxt|msgid` when they have a context. a `|` or `\` inside the context,
+/// the msgid or a plural form is escaped with a `\`, see [`join`] and [`split`].
+/// comments, flags and the other fields of an entry are left untouched.
+pub struct PoFormat;
+
+#[derive(Debug)]
+pub struct Entry {
+    /// first line, comments included
+    start: usize,
+    /// the `msgctxt` or `msgid` line
+    line: usize,
+    /// one past the last line
+    end: usize,
+    pub msgctxt: Option<String>,
+    pub msgid: String,
+    pub msgid_plural: Option<String>,
+    /// the `#,` flags, `fuzzy` among them
+    pub flags: Vec<String>,
+    /// (n for `msgstr[n]`, first line, one past the last line, value)
+    msgstr: Vec<(Option<usize>, usize, usize, String)>,
+}
+impl Entry {
+    pub fn key(&self) -> String {
+        match &self.msgctxt {
+            Some(ctx) => join(&[ctx, &self.msgid]),
+            None => join(&[&self.msgid]),
+        }
+    }
+    pub fn is_translated(&self) -> bool {
+        self.msgstr.iter().all(|(_, _, _, s)| !s.is_empty())
+    }
+    pub fn is_fuzzy(&self) -> bool {
+        self.flags.iter().any(|f| f == "fuzzy")
+    }
+    pub fn is_header(&self) -> bool {
+        self.msgid.is_empty() && self.msgctxt.is_none()
+    }
+}
+
+/// joins parts with `|`, escaping a `|` or `\` inside them
+pub fn join(parts: &[&str]) -> String {
+    let parts: Vec<String> = parts
+        .iter()
+        .map(|p| p.replace('\\', "\\\\").replace('|', "\\|"))
+        .collect();
+    parts.join("|")
+}
+/// splits at every `|` that is not escaped, the reverse of [`join`]
+pub fn split(joined: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = joined.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => parts
+                .last_mut()
+                .expect("starts with a part")
+                .extend(chars.next()),
+            '|' => parts.push(String::new()),
+            c => parts.last_mut().expect("starts with a part").push(c),
+        }
+    }
+    parts
+}
+
+pub fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+fn unquote(quoted: &str) -> String {
+    let inner = quoted
+        .trim()
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or_default();
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
+/// the keyword of a `keyword "string"` line, `msgstr[1]` included
+fn keyword(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start();
+    let (word, rest) = line.split_once(|c: char| c.is_whitespace())?;
+    word.starts_with("msg").then_some((word, rest))
+}
+/// parses all entries, obsolete (`#~`) entries are treated as comments
+pub fn parse(text: &str) -> Result<Vec<Entry>, Error> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut entries = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].trim().is_empty() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut flags = vec![];
+        while i < lines.len() && lines[i].trim_start().starts_with('#') {
+            if let Some(line) = lines[i].trim_start().strip_prefix("#,") {
+                flags.extend(line.split(',').map(|f| f.trim().to_owned()));
+            }
+            i += 1;
+        }
+        let mut entry = Entry {
+            start,
+            line: i,
+            end: i,
+            msgctxt: None,
+            msgid: String::new(),
+            msgid_plural: None,
+            flags,
+            msgstr: vec![],
+        };
+        let mut has_msgid = false;
+        while let Some((word, rest)) = lines.get(i).and_then(|l| keyword(l)) {
+            // a new entry starts once the msgstr of this one has been read
+            if !entry.msgstr.is_empty() && !word.starts_with("msgstr") {
+                break;
+            }
+            let first = i;
+            let mut value = unquote(rest);
+            i += 1;
+            while i < lines.len() && lines[i].trim_start().starts_with('"') {
+                value.push_str(&unquote(lines[i]));
+                i += 1;
+            }
+            match word {
+                "msgctxt" => entry.msgctxt = Some(value),
+                "msgid" => {
+                    entry.msgid = value;
+                    has_msgid = true;
+                }
+                "msgid_plural" => entry.msgid_plural = Some(value),
+                "msgstr" => entry.msgstr.push((None, first, i, value)),
+                word => {
+                    let n = word
+                        .strip_prefix("msgstr[")
+                        .and_then(|n| n.strip_suffix(']'))
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(|| Error::Parse {
+                            line: first + 1,
+                            msg: format!("unknown keyword {word}"),
+                        })?;
+                    entry.msgstr.push((Some(n), first, i, value));
+                }
+            }
+        }
+        entry.end = i;
+        if has_msgid {
+            entries.push(entry);
+        } else if i == start {
+            return Err(Error::Parse {
+                line: i + 1,
+                msg: format!("unexpected line: {}", lines[i]),
+            });
+        }
+    }
+    Ok(entries)
+}
+fn find_entry(entries: &[Entry], key: &str) -> Result<usize, Error> {
+    entries
+        .iter()
+        .position(|e| e.key() == key)
+        .ok_or_else(|| Error::KeyNotFound(key.to_owned()))
+}
+fn entry_lines(key: &str, value: &str) -> Vec<String> {
+    let mut lines = vec![];
+    let mut parts = split(key);
+    let msgid = parts.pop().expect("split yields a part");
+    if !parts.is_empty() {
+        let ctx: Vec<&str> = parts.iter().map(String::as_str).collect();
+        lines.push(format!("msgctxt {}", quote(&ctx.join("|"))));
+    }
+    lines.push(format!("msgid {}", quote(&msgid)));
+    lines.push(format!("msgstr {}", quote(value)));
+    lines
+}
+/// sets the msgstr of `entry`, the last form is repeated for the remaining `msgstr[n]`
+fn set_msgstr(text: &str, entry: &Entry, forms: &[String]) -> String {
+    let mut out = text.to_owned();
+    // back to front so the line numbers stay valid
+    for (i, (n, first, end, _)) in entry.msgstr.iter().enumerate().rev() {
+        let form = forms.get(i).or(forms.last()).expect("split yields a form");
+        let word = match n {
+            Some(n) => format!("msgstr[{n}]"),
+            None => "msgstr".to_owned(),
+        };
+        out = splice(
+            &out,
+            *first,
+            end - first,
+            vec![format!("{word} {}", quote(form))],
+        );
+    }
+    out
+}
+/// fills the empty msgstrs of an entry with a translation for the msgid and one
+/// for the msgid_plural. a language with more than two plural forms only gets
+/// `msgstr[0]` and `msgstr[1]`, the entry is marked fuzzy for the rest.
+pub fn fill(text: &str, key: &str, singular: &str, plural: &str) -> Result<String, Error> {
+    let entries = parse(text)?;
+    let entry = &entries[find_entry(&entries, key)?];
+    let mut out = text.to_owned();
+    // back to front so the line numbers stay valid
+    for (i, (n, first, end, value)) in entry.msgstr.iter().enumerate().rev() {
+        let form = match i {
+            _ if !value.is_empty() => continue,
+            0 => singular,
+            1 => plural,
+            _ => continue,
+        };
+        let word = match n {
+            Some(n) => format!("msgstr[{n}]"),
+            None => "msgstr".to_owned(),
+        };
+        out = splice(
+            &out,
+            *first,
+            end - first,
+            vec![format!("{word} {}", quote(form))],
+        );
+    }
+    if entry.msgstr.len() > 2 && !entry.is_fuzzy() {
+        out = add_fuzzy(&out, entry);
+    }
+    Ok(out)
+}
+/// adds `fuzzy` to the `#,` line of `entry`, or a `#, fuzzy` line when it has none
+fn add_fuzzy(text: &str, entry: &Entry) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let flags = (entry.start..entry.line).find(|&i| lines[i].trim_start().starts_with("#,"));
+    match flags {
+        Some(i) => splice(text, i, 1, vec![format!("{}, fuzzy", lines[i])]),
+        None => splice(text, entry.line, 0, vec!["#, fuzzy".to_owned()]),
+    }
+}
+
+/// the `Plural-Forms` of a language (by its primary subtag) and how many forms it has
+pub fn plural_forms(lang: &str) -> Option<(usize, &'static str)> {
+    let primary = lang.split(['-', '_', '@', '.']).next().unwrap_or(lang);
+    let forms = match primary.to_ascii_lowercase().as_str() {
+        "ja" | "ko" | "zh" | "vi" | "th" | "id" | "ms" | "lo" | "km" => {
+            (1, "nplurals=1; plural=0;")
+        }
+        "en" | "de" | "nl" | "sv" | "da" | "no" | "nb" | "nn" | "fo" | "fy" | "es" | "it"
+        | "el" | "bg" | "fi" | "et" | "he" | "eo" | "hu" | "tr" | "ca" | "gl" | "eu" | "af"
+        | "sq" | "hi" | "bn" | "ur" | "fa" | "sw" => (2, "nplurals=2; plural=(n != 1);"),
+        "fr" | "pt" | "oc" => (2, "nplurals=2; plural=(n > 1);"),
+        "is" => (2, "nplurals=2; plural=(n%10!=1 || n%100==11);"),
+        "lv" => (
+            3,
+            "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n != 0 ? 1 : 2);",
+        ),
+        "lt" => (
+            3,
+            "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && (n%100<10 || n%100>=20) ? 1 : 2);",
+        ),
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" => (
+            3,
+            "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);",
+        ),
+        "cs" | "sk" => (3, "nplurals=3; plural=(n==1 ? 0 : n>=2 && n<=4 ? 1 : 2);"),
+        "pl" => (
+            3,
+            "nplurals=3; plural=(n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);",
+        ),
+        "ro" => (
+            3,
+            "nplurals=3; plural=(n==1 ? 0 : (n==0 || (n%100 > 0 && n%100 < 20)) ? 1 : 2);",
+        ),
+        "sl" => (
+            4,
+            "nplurals=4; plural=(n%100==1 ? 0 : n%100==2 ? 1 : n%100==3 || n%100==4 ? 2 : 3);",
+        ),
+        "cy" => (
+            4,
+            "nplurals=4; plural=(n==1 ? 0 : n==2 ? 1 : (n != 8 && n != 11) ? 2 : 3);",
+        ),
+        "ga" => (
+            5,
+            "nplurals=5; plural=(n==1 ? 0 : n==2 ? 1 : n>=3 && n<=6 ? 2 : n>=7 && n<=10 ? 3 : 4);",
+        ),
+        "ar" => (
+            6,
+            "nplurals=6; plural=(n==0 ? 0 : n==1 ? 1 : n==2 ? 2 : n%100>=3 && n%100<=10 ? 3 : n%100>=11 ? 4 : 5);",
+        ),
+        _ => return None,
+    };
+    Some(forms)
+}
+/// sets the `name: value` line of a header, adding it when missing
+fn set_field(fields: &mut Vec<String>, name: &str, value: &str) {
+    let field = format!("{name}: {value}");
+    match fields
+        .iter()
+        .position(|f| f.starts_with(&format!("{name}:")))
+    {
+        Some(i) => fields[i] = field,
+        None => fields.push(field),
+    }
+}
+/// the `msgstr` lines of a header with the `Language` and `Plural-Forms` of `lang`
+fn header_lines(header: &str, lang: &str, plural: Option<&str>) -> Vec<String> {
+    let mut fields: Vec<String> = header.lines().map(ToOwned::to_owned).collect();
+    set_field(&mut fields, "Language", lang);
+    if let Some(plural) = plural {
+        set_field(&mut fields, "Plural-Forms", plural);
+    }
+    let mut lines = vec!["msgstr \"\"".to_owned()];
+    lines.extend(fields.iter().map(|f| quote(&format!("{f}\n"))));
+    lines
+}
+/// a catalog for `lang` from a `.pot` template: the `Language` and `Plural-Forms`
+/// headers are set and plural entries get an empty `msgstr[n]` per form of the
+/// language. languages without known plural forms keep those of the template.
+pub fn from_template(template: &str, lang: &str) -> Result<String, Error> {
+    let entries = parse(template)?;
+    let plural = plural_forms(lang);
+    let mut text = template.to_owned();
+    // back to front so the line numbers stay valid
+    for entry in entries.iter().rev() {
+        let (Some((_, first, _, header)), Some((_, _, end, _))) =
+            (entry.msgstr.first(), entry.msgstr.last())
+        else {
+            continue;
+        };
+        if entry.is_header() {
+            let lines = header_lines(header, lang, plural.map(|(_, p)| p));
+            text = splice(&text, *first, end - first, lines);
+        } else if let (Some(_), Some((n, _))) = (&entry.msgid_plural, plural) {
+            let lines = (0..n).map(|i| format!("msgstr[{i}] \"\"")).collect();
+            text = splice(&text, *first, end - first, lines);
+        }
+    }
+    if !entries.iter().any(Entry::is_header) {
+        let mut lines = vec!["msgid \"\"".to_owned()];
+        lines.extend(header_lines("", lang, plural.map(|(_, p)| p)));
+        lines.push(String::new());
+        text = splice(&text, 0, 0, lines);
+    }
+    Ok(text + "\n")
+}
+
+impl LangFormat for PoFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        let entries = parse(text).ok()?;
+        find_entry(&entries, key).ok().map(|i| entries[i].line)
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        if find_entry(&entries, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let target = &entries[find_entry(&entries, before)?];
+        let mut lines = entry_lines(key, value);
+        lines.push(String::new());
+        Ok(splice(text, target.start, 0, lines))
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        if find_entry(&entries, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let mut lines = entry_lines(key, value);
+        if !text.trim().is_empty() {
+            lines.insert(0, String::new());
+        }
+        let end = text.trim_end().lines().count();
+        Ok(splice(text, end, usize::MAX, lines))
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        let target = &entries[find_entry(&entries, key)?];
+        // take the blank line that separates it from the next entry along
+        let lines: Vec<&str> = text.lines().collect();
+        let mut end = target.end;
+        while end < lines.len() && lines[end].trim().is_empty() {
+            end += 1;
+        }
+        Ok(splice(text, target.start, end - target.start, vec![]))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        let entry = &entries[find_entry(&entries, key)?];
+        // plural forms are given joined, see [`join`]
+        let forms = match entry.msgid_plural {
+            Some(_) => split(value),
+            None => vec![value.to_owned()],
+        };
+        Ok(set_msgstr(text, entry, &forms))
+    }
+    /// plural forms are joined with [`join`], the header is left out
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let entries = parse(text)?
+            .into_iter()
+            .filter(|e| !e.is_header())
+            .map(|e| {
+                let forms: Vec<&str> = e.msgstr.iter().map(|(_, _, _, s)| s.as_str()).collect();
+                let value = match e.msgid_plural {
+                    Some(_) => join(&forms),
+                    None => forms.concat(),
+                };
+                (e.key(), value)
+            });
+        Ok(entries.collect())
+    }
+}
@@ -0,0 +1,160 @@
+use super::{splice, Error, LangFormat};
+use crate::serializer::{unescape_properties, ValueSerializer};
+
+/// java `.properties` files, keys are matched exactly after unescaping
+pub struct PropertiesFormat;
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    /// first physical line of the entry
+    line: usize,
+    /// one past the last physical line (continuations included)
+    end: usize,
+    /// byte length of `key` + separator as written on the first line
+    head: usize,
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.is_empty() || line.starts_with('#') || line.starts_with('!')
+}
+/// a line ending in an odd number of backslashes continues on the next line
+fn continues(line: &str) -> bool {
+    (line.len() - line.trim_end_matches('\\').len()) % 2 == 1
+}
+/// splits the first line of an entry into its unescaped key and the length of key + separator
+fn split_key(line: &str) -> (String, usize) {
+    let mut chars = line
+        .char_indices()
+        .skip_while(|(_, c)| c.is_whitespace() || *c == '\u{0c}');
+    let mut raw_key = String::new();
+    let mut key_end = line.len();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                raw_key.push(c);
+                if let Some((_, next)) = chars.next() {
+                    raw_key.push(next);
+                }
+            }
+            '=' | ':' => {
+                key_end = i;
+                break;
+            }
+            c if c.is_whitespace() => {
+                key_end = i;
+                break;
+            }
+            c => raw_key.push(c),
+        }
+    }
+    // the separator is whitespace, optionally with one `=` or `:` in it
+    let rest = &line[key_end..];
+    let trimmed = rest.trim_start();
+    let trimmed = trimmed
+        .strip_prefix(['=', ':'])
+        .map_or(trimmed, str::trim_start);
+    (unescape_properties(&raw_key), line.len() - trimmed.len())
+}
+fn parse(text: &str) -> Vec<Entry> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut entries = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if is_comment(line) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < lines.len() && continues(lines[i]) {
+            i += 1;
+        }
+        i += 1;
+        let first = if continues(line) {
+            &line[..line.len() - 1]
+        } else {
+            line
+        };
+        let (key, head) = split_key(first);
+        entries.push(Entry {
+            key,
+            line: start,
+            end: i.min(lines.len()),
+            head,
+        });
+    }
+    entries
+}
+fn find_entry(text: &str, key: &str) -> Result<Entry, Error> {
+    parse(text)
+        .into_iter()
+        .find(|e| e.key == key)
+        .ok_or_else(|| Error::KeyNotFound(key.to_owned()))
+}
+
+impl LangFormat for PropertiesFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        find_entry(text, key).ok().map(|e| e.line)
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        if self.find(text, key).is_some() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let target = find_entry(text, before)?;
+        let entry = ValueSerializer::Properties.entry(key, value);
+        Ok(splice(text, target.line, 0, vec![entry]))
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        if self.find(text, key).is_some() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let entry = ValueSerializer::Properties.entry(key, value);
+        Ok(splice(text, usize::MAX, 0, vec![entry]))
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let target = find_entry(text, key)?;
+        Ok(splice(text, target.line, target.end - target.line, vec![]))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let target = find_entry(text, key)?;
+        let line = text.lines().nth(target.line).expect("entry line exists");
+        // keep the key and separator as written
+        let head = &line[..target.head];
+        let separator = if head.ends_with(['=', ':', ' ', '\t']) {
+            ""
+        } else {
+            "="
+        };
+        let entry = format!(
+            "{head}{separator}{}",
+            ValueSerializer::Properties.quote(value)
+        );
+        Ok(splice(
+            text,
+            target.line,
+            target.end - target.line,
+            vec![entry],
+        ))
+    }
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let lines: Vec<&str> = text.lines().collect();
+        let entries = parse(text).into_iter().map(|entry| {
+            let mut raw = String::new();
+            for (i, line) in lines[entry.line..entry.end].iter().enumerate() {
+                let line = match i {
+                    0 => &line[entry.head..],
+                    _ => line.trim_start(),
+                };
+                if continues(line) {
+                    raw.push_str(&line[..line.len() - 1]);
+                } else {
+                    raw.push_str(line);
+                }
+            }
+            (entry.key, ValueSerializer::Properties.unquote(&raw))
+        });
+        Ok(entries.collect())
+    }
+}
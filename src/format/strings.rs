@@ -0,0 +1,216 @@
+use super::{line_of, splice, Error, LangFormat};
+use std::ops::Range;
+
+/// apple `<lang>.lproj/*.strings` files of `"key" = "value";` pairs. a comment
+/// right above an entry (as xcode writes them) moves along with it.
+pub struct StringsFormat;
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    value: String,
+    /// start of the attached comment, or of the key
+    start: usize,
+    key_start: usize,
+    /// the quoted value
+    value_range: Range<usize>,
+    /// one past the `;`
+    end: usize,
+}
+
+fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+fn entry_text(key: &str, value: &str) -> String {
+    format!("{} = {};", quote(key), quote(value))
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> Error {
+        Error::Parse {
+            line: line_of(self.text, self.pos.min(self.text.len())) + 1,
+            msg: msg.to_owned(),
+        }
+    }
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+    /// skips whitespace and comments, returns where the comment attached to
+    /// what follows starts (one not separated from it by a blank line)
+    fn skip(&mut self) -> Result<Option<usize>, Error> {
+        let mut comment = None;
+        loop {
+            let ws = self.rest().len() - self.rest().trim_start().len();
+            if self.rest()[..ws].matches('\n').count() > 1 {
+                comment = None;
+            }
+            self.pos += ws;
+            let end = match self.rest() {
+                r if r.starts_with("/*") => r.find("*/").map(|i| i + 2),
+                r if r.starts_with("//") => Some(r.find('\n').unwrap_or(r.len())),
+                _ => return Ok(comment),
+            };
+            let end = end.ok_or_else(|| self.error("unterminated comment"))?;
+            comment = comment.or(Some(self.pos));
+            self.pos += end;
+        }
+    }
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        self.skip()?;
+        if !self.rest().starts_with(c) {
+            return Err(self.error(&format!("expected '{c}'")));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+    /// a quoted string, or a bare word as old style plists allow
+    fn string(&mut self) -> Result<String, Error> {
+        if !self.rest().starts_with('"') {
+            let len = self
+                .rest()
+                .find(|c: char| !(c.is_alphanumeric() || "_.$/:-".contains(c)))
+                .unwrap_or(self.rest().len());
+            if len == 0 {
+                return Err(self.error("expected a string"));
+            }
+            self.pos += len;
+            return Ok(self.text[self.pos - len..self.pos].to_owned());
+        }
+        let mut out = String::new();
+        let mut chars = self.rest()[1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 2;
+                    return Ok(out);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('U' | 'u') => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        out.extend(c);
+                    }
+                    Some(c) => out.push(c),
+                    None => {}
+                },
+                c => out.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+}
+fn parse(text: &str) -> Result<Vec<Entry>, Error> {
+    let mut parser = Parser { text, pos: 0 };
+    let mut entries = vec![];
+    loop {
+        let comment = parser.skip()?;
+        if parser.pos == text.len() {
+            return Ok(entries);
+        }
+        let key_start = parser.pos;
+        let key = parser.string()?;
+        parser.expect('=')?;
+        parser.skip()?;
+        let value_start = parser.pos;
+        let value = parser.string()?;
+        let value_range = value_start..parser.pos;
+        parser.expect(';')?;
+        entries.push(Entry {
+            key,
+            value,
+            start: comment.unwrap_or(key_start),
+            key_start,
+            value_range,
+            end: parser.pos,
+        });
+    }
+}
+fn find_entry<'a>(entries: &'a [Entry], key: &str) -> Result<&'a Entry, Error> {
+    entries
+        .iter()
+        .find(|e| e.key == key)
+        .ok_or_else(|| Error::KeyNotFound(key.to_owned()))
+}
+
+impl LangFormat for StringsFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        let entries = parse(text).ok()?;
+        find_entry(&entries, key)
+            .ok()
+            .map(|e| line_of(text, e.key_start))
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        if find_entry(&entries, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let target = find_entry(&entries, before)?;
+        let at = line_of(text, target.start);
+        let mut lines = vec![entry_text(key, value)];
+        // keep the blank line between entries when the file uses them
+        if at > 0
+            && text
+                .lines()
+                .nth(at - 1)
+                .is_some_and(|l| l.trim().is_empty())
+        {
+            lines.push(String::new());
+        }
+        Ok(splice(text, at, 0, lines))
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        if find_entry(&entries, key).is_ok() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let mut lines = vec![entry_text(key, value)];
+        if text.contains("\n\n") {
+            lines.insert(0, String::new());
+        }
+        let end = text.trim_end().lines().count();
+        Ok(splice(text, end, usize::MAX, lines))
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        let target = find_entry(&entries, key)?;
+        // take the blank line that separates it from the next entry along
+        let lines: Vec<&str> = text.lines().collect();
+        let start = line_of(text, target.start);
+        let mut end = line_of(text, target.end) + 1;
+        while end < lines.len() && lines[end].trim().is_empty() {
+            end += 1;
+        }
+        Ok(splice(text, start, end - start, vec![]))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let entries = parse(text)?;
+        let target = find_entry(&entries, key)?;
+        let mut out = text.to_owned();
+        out.replace_range(target.value_range.clone(), &quote(value));
+        Ok(out)
+    }
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let entries = parse(text)?.into_iter().map(|e| (e.key, e.value));
+        Ok(entries.collect())
+    }
+}
@@ -0,0 +1,245 @@
+use super::{line_of, Error, LangFormat};
+use serde_json::{json, Map, Value};
+
+/// apple string catalogs (`Localizable.xcstrings`), one json file holding every
+/// language. edits work on the localizations of `lang`, the variations of a
+/// plural are addressed as `key.quantity`. the source language falls back to
+/// the key, removing a key for it removes the key from the catalog. the file is
+/// written back laid out as xcode does, new keys sorted in.
+pub struct XcstringsFormat {
+    pub lang: String,
+}
+
+fn parse(text: &str) -> Result<Map<String, Value>, Error> {
+    let error = |line, msg: &str| Error::Parse {
+        line,
+        msg: msg.to_owned(),
+    };
+    match serde_json::from_str(text) {
+        Ok(Value::Object(root)) => Ok(root),
+        Ok(_) => Err(error(1, "expected a string catalog object")),
+        Err(e) => Err(error(e.line(), &e.to_string())),
+    }
+}
+fn strings(root: &Map<String, Value>) -> Option<&Map<String, Value>> {
+    root.get("strings")?.as_object()
+}
+fn strings_mut(root: &mut Map<String, Value>) -> &mut Map<String, Value> {
+    object(child(root, "strings", json!({})))
+}
+fn unit_value(unit: &Value) -> Option<&str> {
+    unit.pointer("/stringUnit/value")?.as_str()
+}
+fn string_unit(value: &str) -> Value {
+    json!({ "stringUnit": { "state": "translated", "value": value } })
+}
+
+/// how `key.quantity` splits, when `key` has plural variations for `lang`
+fn plural_of<'a>(
+    root: &Map<String, Value>,
+    lang: &str,
+    key: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    let strings = strings(root)?;
+    let (base, quantity) = key
+        .rsplit_once('.')
+        .filter(|_| !strings.contains_key(key))?;
+    let plural = format!("/localizations/{lang}/variations/plural");
+    strings
+        .get(base)?
+        .pointer(&plural)?
+        .is_object()
+        .then_some((base, quantity))
+}
+
+/// `value` as an object, anything else is replaced by an empty one
+fn object(value: &mut Value) -> &mut Map<String, Value> {
+    if !value.is_object() {
+        *value = json!({});
+    }
+    value.as_object_mut().expect("made an object")
+}
+/// the value of `key`, `new` is added where xcode would sort it when missing
+fn child<'a>(map: &'a mut Map<String, Value>, key: &str, new: Value) -> &'a mut Value {
+    if !map.contains_key(key) {
+        insert_sorted(map, key, new);
+    }
+    map.get_mut(key).expect("inserted")
+}
+/// adds `key` in case insensitive order, the order of the file is kept otherwise
+fn insert_sorted(map: &mut Map<String, Value>, key: &str, value: Value) {
+    let lower = key.to_lowercase();
+    let entries = std::mem::take(map);
+    let mut value = Some(value);
+    for (k, v) in entries {
+        if k.to_lowercase() > lower {
+            if let Some(value) = value.take() {
+                map.insert(key.to_owned(), value);
+            }
+        }
+        map.insert(k, v);
+    }
+    if let Some(value) = value {
+        map.insert(key.to_owned(), value);
+    }
+}
+
+/// the xcode layout: two space indents and ` : ` between key and value
+fn write(value: &Value, indent: &str, out: &mut String) {
+    let inner = format!("{indent}  ");
+    match value {
+        Value::Object(map) if map.is_empty() => out.push_str(&format!("{{\n\n{indent}}}")),
+        Value::Object(map) => {
+            out.push_str("{\n");
+            for (i, (key, value)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                out.push_str(&inner);
+                out.push_str(&serde_json::to_string(key).expect("strings serialize"));
+                out.push_str(" : ");
+                write(value, &inner, out);
+            }
+            out.push_str(&format!("\n{indent}}}"));
+        }
+        Value::Array(items) if items.is_empty() => out.push_str("[\n\n]"),
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for (i, value) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(",\n");
+                }
+                out.push_str(&inner);
+                write(value, &inner, out);
+            }
+            out.push_str(&format!("\n{indent}]"));
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
+fn to_text(root: Map<String, Value>) -> String {
+    let mut out = String::new();
+    write(&Value::Object(root), "", &mut out);
+    out.push('\n');
+    out
+}
+
+impl XcstringsFormat {
+    fn is_source(&self, root: &Map<String, Value>) -> bool {
+        root.get("sourceLanguage").and_then(Value::as_str) == Some(&self.lang)
+    }
+    /// the localization of `lang` for `key`, created when missing
+    fn localization<'a>(&self, root: &'a mut Map<String, Value>, key: &str) -> &'a mut Value {
+        let entry = child(
+            strings_mut(root),
+            key,
+            json!({ "extractionState": "manual" }),
+        );
+        let localizations = child(object(entry), "localizations", json!({}));
+        child(object(localizations), &self.lang, json!({}))
+    }
+    /// sets the value of `lang`, marking it translated
+    fn set(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let mut root = parse(text)?;
+        match plural_of(&root, &self.lang, key) {
+            Some((base, quantity)) => {
+                let localization = self.localization(&mut root, base);
+                let plural = &mut localization["variations"]["plural"];
+                *child(object(plural), quantity, json!({})) = string_unit(value);
+            }
+            None => *self.localization(&mut root, key) = string_unit(value),
+        }
+        Ok(to_text(root))
+    }
+}
+
+impl LangFormat for XcstringsFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        if !self.entries(text).ok()?.iter().any(|(k, _)| k == key) {
+            return None;
+        }
+        let root = parse(text).ok()?;
+        let base = plural_of(&root, &self.lang, key).map_or(key, |(base, _)| base);
+        // the line of the key in the `strings` object
+        let strings = text.find("\"strings\"")?;
+        let quoted = serde_json::to_string(base).ok()?;
+        let at = text[strings..]
+            .match_indices(&quoted)
+            .map(|(i, _)| strings + i)
+            .find(|&i| text[i + quoted.len()..].trim_start().starts_with(':'))?;
+        Some(line_of(text, at))
+    }
+    /// the catalog is sorted by key, the entry is added like with `append`
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        if self.find(text, before).is_none() {
+            return Err(Error::KeyNotFound(before.to_owned()));
+        }
+        self.append(text, key, value)
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        if self.entries(text)?.iter().any(|(k, _)| k == key) {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        self.set(text, key, value)
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        if !self.entries(text)?.iter().any(|(k, _)| k == key) {
+            return Err(Error::KeyNotFound(key.to_owned()));
+        }
+        let mut root = parse(text)?;
+        let is_source = self.is_source(&root);
+        match plural_of(&root, &self.lang, key) {
+            Some((base, quantity)) => {
+                let plural = &mut self.localization(&mut root, base)["variations"]["plural"];
+                if let Some(plural) = plural.as_object_mut() {
+                    plural.shift_remove(quantity);
+                }
+            }
+            None if is_source => {
+                strings_mut(&mut root).shift_remove(key);
+            }
+            None => {
+                let entry = strings_mut(&mut root).get_mut(key).expect("listed");
+                if let Some(localizations) = entry["localizations"].as_object_mut() {
+                    localizations.shift_remove(&self.lang);
+                }
+            }
+        }
+        Ok(to_text(root))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        if !self.entries(text)?.iter().any(|(k, _)| k == key) {
+            return Err(Error::KeyNotFound(key.to_owned()));
+        }
+        self.set(text, key, value)
+    }
+    /// keys marked `shouldTranslate: false` are left out
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let root = parse(text)?;
+        let is_source = self.is_source(&root);
+        let mut out = vec![];
+        for (key, entry) in strings(&root).into_iter().flatten() {
+            if entry.get("shouldTranslate") == Some(&Value::Bool(false)) {
+                continue;
+            }
+            let localization = entry.get("localizations").and_then(|l| l.get(&self.lang));
+            let plural = localization
+                .and_then(|l| l.pointer("/variations/plural"))
+                .and_then(Value::as_object);
+            match (localization.and_then(unit_value), plural) {
+                (Some(value), _) => out.push((key.clone(), value.to_owned())),
+                (None, Some(plural)) => {
+                    for (quantity, unit) in plural {
+                        if let Some(value) = unit_value(unit) {
+                            out.push((format!("{key}.{quantity}"), value.to_owned()));
+                        }
+                    }
+                }
+                // the source text is the key until it gets a value of its own
+                (None, None) if is_source => out.push((key.clone(), key.clone())),
+                (None, None) => {}
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,197 @@
+use super::{line_of, xml, Error};
+
+/// xliff exchange files for translation agencies and their cat tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    V1_2,
+    V2_0,
+}
+impl Version {
+    pub const NAMES: [&'static str; 2] = ["1.2", "2.0"];
+}
+#[derive(Debug)]
+pub struct Unit {
+    pub key: String,
+    pub source: String,
+    pub target: Option<String>,
+}
+#[derive(Debug)]
+pub struct File {
+    /// the path of the file relative to its language
+    pub original: String,
+    pub units: Vec<Unit>,
+}
+#[derive(Debug)]
+pub struct Document {
+    pub version: Version,
+    pub source_language: String,
+    pub target_language: String,
+    pub files: Vec<File>,
+}
+
+pub fn write(doc: &Document) -> String {
+    let e = xml::escape;
+    let (src, trg) = (e(&doc.source_language), e(&doc.target_language));
+    let mut lines = vec![r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned()];
+    match doc.version {
+        Version::V1_2 => {
+            lines.push(
+                r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">"#.to_owned(),
+            );
+            for file in &doc.files {
+                lines.push(format!(
+                    r#"  <file original="{}" source-language="{src}" target-language="{trg}" datatype="plaintext">"#,
+                    e(&file.original)
+                ));
+                lines.push("    <body>".to_owned());
+                for unit in &file.units {
+                    let key = e(&unit.key);
+                    lines.push(format!(
+                        r#"      <trans-unit id="{key}" resname="{key}" xml:space="preserve">"#
+                    ));
+                    lines.push(format!("        <source>{}</source>", e(&unit.source)));
+                    if let Some(target) = &unit.target {
+                        lines.push(format!(
+                            r#"        <target state="translated">{}</target>"#,
+                            e(target)
+                        ));
+                    }
+                    lines.push("      </trans-unit>".to_owned());
+                }
+                lines.push("    </body>".to_owned());
+                lines.push("  </file>".to_owned());
+            }
+        }
+        Version::V2_0 => {
+            lines.push(format!(
+                r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="{src}" trgLang="{trg}">"#
+            ));
+            for (f, file) in doc.files.iter().enumerate() {
+                lines.push(format!(
+                    r#"  <file id="f{}" original="{}">"#,
+                    f + 1,
+                    e(&file.original)
+                ));
+                // ids have to be nmtokens, the key goes in the name
+                for (u, unit) in file.units.iter().enumerate() {
+                    lines.push(format!(
+                        r#"    <unit id="u{}" name="{}">"#,
+                        u + 1,
+                        e(&unit.key)
+                    ));
+                    match &unit.target {
+                        Some(_) => lines.push(r#"      <segment state="translated">"#.to_owned()),
+                        None => lines.push("      <segment>".to_owned()),
+                    }
+                    lines.push(format!(
+                        r#"        <source xml:space="preserve">{}</source>"#,
+                        e(&unit.source)
+                    ));
+                    if let Some(target) = &unit.target {
+                        lines.push(format!(
+                            r#"        <target xml:space="preserve">{}</target>"#,
+                            e(target)
+                        ));
+                    }
+                    lines.push("      </segment>".to_owned());
+                    lines.push("    </unit>".to_owned());
+                }
+                lines.push("  </file>".to_owned());
+            }
+        }
+    }
+    lines.push("</xliff>".to_owned());
+    lines.join("\n") + "\n"
+}
+
+/// the units below `element`, groups included
+fn units<'a>(element: &'a xml::Element, name: &str, out: &mut Vec<&'a xml::Element>) {
+    for child in &element.children {
+        match child.name.as_str() {
+            n if n == name => out.push(child),
+            _ => units(child, name, out),
+        }
+    }
+}
+pub fn read(text: &str) -> Result<Document, Error> {
+    let root = xml::parse(text)?;
+    let error = |element: &xml::Element, msg: &str| Error::Parse {
+        line: line_of(text, element.start) + 1,
+        msg: msg.to_owned(),
+    };
+    if root.name != "xliff" {
+        return Err(error(&root, "not an xliff document"));
+    }
+    let version = match root.attribute("version") {
+        Some(v) if v.starts_with("1.") => Version::V1_2,
+        Some(v) if v.starts_with("2.") => Version::V2_0,
+        _ => return Err(error(&root, "unsupported xliff version")),
+    };
+    let mut doc = Document {
+        version,
+        source_language: root.attribute("srcLang").unwrap_or_default().to_owned(),
+        target_language: root.attribute("trgLang").unwrap_or_default().to_owned(),
+        files: vec![],
+    };
+    for file in root.children_named("file") {
+        let original = file
+            .attribute("original")
+            .ok_or_else(|| error(file, "file without original"))?;
+        let mut found = vec![];
+        let units = match version {
+            Version::V1_2 => {
+                if let Some(lang) = file.attribute("source-language") {
+                    doc.source_language = lang.to_owned();
+                }
+                if let Some(lang) = file.attribute("target-language") {
+                    doc.target_language = lang.to_owned();
+                }
+                units(file, "trans-unit", &mut found);
+                found
+                    .into_iter()
+                    .map(|unit| {
+                        let key = unit.attribute("resname").or(unit.attribute("id"));
+                        Ok(Unit {
+                            key: key
+                                .ok_or_else(|| error(unit, "unit without id"))?
+                                .to_owned(),
+                            source: unit.child("source").map_or(String::new(), |s| s.text(text)),
+                            target: unit.child("target").map(|t| t.text(text)),
+                        })
+                    })
+                    .collect::<Result<Vec<Unit>, Error>>()?
+            }
+            Version::V2_0 => {
+                units(file, "unit", &mut found);
+                found
+                    .into_iter()
+                    .map(|unit| {
+                        let key = unit.attribute("name").or(unit.attribute("id"));
+                        // segments (and the ignorables between them) make up the text
+                        let parts = || {
+                            unit.children
+                                .iter()
+                                .filter(|c| c.name == "segment" || c.name == "ignorable")
+                        };
+                        let source = parts().filter_map(|s| s.child("source"));
+                        let targets: Vec<&xml::Element> =
+                            parts().filter_map(|s| s.child("target")).collect();
+                        Ok(Unit {
+                            key: key
+                                .ok_or_else(|| error(unit, "unit without id"))?
+                                .to_owned(),
+                            source: source.map(|s| s.text(text)).collect(),
+                            target: (!targets.is_empty())
+                                .then(|| targets.iter().map(|t| t.text(text)).collect()),
+                        })
+                    })
+                    .collect::<Result<Vec<Unit>, Error>>()?
+            }
+        };
+        doc.files.push(File {
+            original: original.to_owned(),
+            units,
+        });
+    }
+    Ok(doc)
+}
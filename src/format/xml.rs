@@ -0,0 +1,242 @@
+use super::{line_of, Error};
+use std::ops::Range;
+
+/// an element of an xml document with the byte ranges it was read from, so
+/// files can be edited in place
+#[derive(Debug)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    /// position of `<`
+    pub start: usize,
+    /// one past the closing `>`
+    pub end: usize,
+    /// the content between the tags, empty for `<x/>`
+    pub inner: Range<usize>,
+    pub children: Vec<Element>,
+}
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+    /// the unescaped text content, the tags of nested elements left out
+    pub fn text(&self, doc: &str) -> String {
+        let mut out = String::new();
+        let mut rest = &doc[self.inner.clone()];
+        while !rest.is_empty() {
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").unwrap_or(cdata.len());
+                out.push_str(&cdata[..end]);
+                rest = cdata.get(end + 3..).unwrap_or("");
+            } else if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            } else if rest.starts_with('<') {
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                out.push_str(&unescape(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+        out
+    }
+}
+
+/// escapes text and attribute values alike
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+/// resolves the predefined entities and character references
+pub fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let resolved = match &rest[1..semi] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match resolved {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> Error {
+        Error::Parse {
+            line: line_of(self.text, self.pos.min(self.text.len())) + 1,
+            msg: msg.to_owned(),
+        }
+    }
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+    fn skip_ws(&mut self) {
+        self.pos = self.text.len() - self.rest().trim_start().len();
+    }
+    /// moves past `end`, which has to follow somewhere
+    fn skip_past(&mut self, end: &str) -> Result<(), Error> {
+        let at = self
+            .rest()
+            .find(end)
+            .ok_or_else(|| self.error(&format!("missing '{end}'")))?;
+        self.pos += at + end.len();
+        Ok(())
+    }
+    /// skips comments, processing instructions and the doctype
+    fn skip_misc(&mut self) -> Result<(), Error> {
+        loop {
+            self.skip_ws();
+            match self.rest() {
+                r if r.starts_with("<!--") => self.skip_past("-->")?,
+                r if r.starts_with("<?") => self.skip_past("?>")?,
+                r if r.starts_with("<!") => self.skip_past(">")?,
+                _ => return Ok(()),
+            }
+        }
+    }
+    fn name(&mut self) -> Result<String, Error> {
+        let len = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || "/>=".contains(c))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(self.text[self.pos - len..self.pos].to_owned())
+    }
+    fn element(&mut self) -> Result<Element, Error> {
+        let start = self.pos;
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected '<'"));
+        }
+        self.pos += 1;
+        let name = self.name()?;
+        let mut attributes = vec![];
+        loop {
+            self.skip_ws();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(Element {
+                    name,
+                    attributes,
+                    start,
+                    end: self.pos,
+                    inner: self.pos..self.pos,
+                    children: vec![],
+                });
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let attribute = self.name()?;
+            self.skip_ws();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected '='"));
+            }
+            self.pos += 1;
+            self.skip_ws();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(self.error("expected a quoted value")),
+            };
+            let len = self.rest()[1..]
+                .find(quote)
+                .ok_or_else(|| self.error("unterminated attribute value"))?;
+            attributes.push((
+                attribute,
+                unescape(&self.text[self.pos + 1..self.pos + 1 + len]),
+            ));
+            self.pos += len + 2;
+        }
+        let inner_start = self.pos;
+        let mut children = vec![];
+        loop {
+            let next = self
+                .rest()
+                .find('<')
+                .ok_or_else(|| self.error(&format!("missing '</{name}>'")))?;
+            self.pos += next;
+            match self.rest() {
+                r if r.starts_with("</") => break,
+                r if r.starts_with("<!--") => self.skip_past("-->")?,
+                r if r.starts_with("<![CDATA[") => self.skip_past("]]>")?,
+                r if r.starts_with("<?") => self.skip_past("?>")?,
+                _ => children.push(self.element()?),
+            }
+        }
+        let inner = inner_start..self.pos;
+        self.pos += 2;
+        if self.name()? != name {
+            return Err(self.error(&format!("expected '</{name}>'")));
+        }
+        self.skip_past(">")?;
+        Ok(Element {
+            name,
+            attributes,
+            start,
+            end: self.pos,
+            inner,
+            children,
+        })
+    }
+}
+/// the root element of `text`
+pub fn parse(text: &str) -> Result<Element, Error> {
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos != text.len() {
+        return Err(parser.error("trailing characters after the root element"));
+    }
+    Ok(root)
+}
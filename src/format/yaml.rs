@@ -0,0 +1,499 @@
+use super::{splice, Error, LangFormat};
+
+/// block style yaml mappings addressed with dot paths. a file with a single
+/// locale key at the top (`nl:`, rails / i18next style) is entered implicitly.
+/// edits only touch the affected lines, so comments and ordering are kept.
+/// flow mappings (`{ ... }`) are refused with a parse error.
+pub struct YamlFormat;
+
+#[derive(Debug)]
+struct Node {
+    key: String,
+    line: usize,
+    indent: usize,
+    /// one past the last line that belongs to the node (children or continuations)
+    end: usize,
+    /// true for `key:` without a value on the line
+    mapping: bool,
+    children: Vec<Node>,
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+fn is_content(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && !line.starts_with('#')
+}
+/// the unquoted key of a `key: value` line and the position of its colon
+fn split_key(line: &str) -> Option<(String, usize)> {
+    let offset = indent_of(line);
+    let line = &line[offset..];
+    if line.starts_with("- ") || line == "-" || line.starts_with("---") {
+        return None;
+    }
+    let (key, key_end) = match line.chars().next()? {
+        '"' => {
+            let end = line[1..].find('"')? + 2;
+            (serde_json::from_str(&line[..end]).ok()?, end)
+        }
+        '\'' => {
+            let end = line[1..].find('\'')? + 2;
+            (line[1..end - 1].to_owned(), end)
+        }
+        _ => {
+            let colon = line
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| line[i + 1..].is_empty() || line[i + 1..].starts_with(' '))?;
+            (line[..colon].trim_end().to_owned(), colon)
+        }
+    };
+    let colon = key_end + line[key_end..].find(':')?;
+    line[key_end..colon]
+        .trim()
+        .is_empty()
+        .then_some((key, offset + colon))
+}
+/// the value text after the colon of a key line
+fn value_of(line: &str, colon: usize) -> &str {
+    line[colon + 1..].trim()
+}
+/// the byte offset just past the closing quote of the quoted scalar `value`
+fn quoted_end(value: &str) -> Option<usize> {
+    let quote = value.chars().next()?;
+    let mut chars = value.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            // `''` is an escaped quote
+            '\'' if quote == '\'' && chars.peek().is_some_and(|&(_, c)| c == '\'') => {
+                chars.next();
+            }
+            c if c == quote => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+/// the ` # comment` after a single line scalar
+fn trailing_comment(value: &str) -> Option<&str> {
+    let rest = match value.chars().next()? {
+        '"' | '\'' => &value[quoted_end(value)?..],
+        _ => &value[value.find(" #")?..],
+    };
+    rest.trim_start().starts_with('#').then_some(rest)
+}
+/// flow mappings (`nl: { title: Home }`) can't be edited line by line
+fn flow_error(line: usize) -> Error {
+    Error::Parse {
+        line: line + 1,
+        msg: "flow mappings ({ ... }) are not supported, write the mapping in block style"
+            .to_owned(),
+    }
+}
+/// parses the block starting at `*i` whose entries are indented deeper than `parent`
+fn parse_block(lines: &[&str], i: &mut usize, parent: Option<usize>) -> Result<Vec<Node>, Error> {
+    let mut nodes = vec![];
+    let mut block_indent = None;
+    while *i < lines.len() {
+        let line = lines[*i];
+        if !is_content(line) {
+            *i += 1;
+            continue;
+        }
+        let indent = indent_of(line);
+        if parent.is_some_and(|p| indent <= p) || block_indent.is_some_and(|b| indent < b) {
+            break;
+        }
+        let block = *block_indent.get_or_insert(indent);
+        let start = *i;
+        *i += 1;
+        if line.trim_start().starts_with('{') {
+            return Err(flow_error(start));
+        }
+        let key_value = (indent == block).then(|| split_key(line)).flatten();
+        let flow = key_value
+            .as_ref()
+            .is_some_and(|&(_, colon)| value_of(line, colon).starts_with('{'));
+        if flow {
+            return Err(flow_error(start));
+        }
+        let mapping = key_value.as_ref().is_some_and(|&(_, colon)| {
+            let v = value_of(line, colon);
+            v.is_empty() || v.starts_with('#') || v.starts_with('&') && !v.contains(' ')
+        });
+        let children = if mapping {
+            parse_block(lines, i, Some(indent))?
+        } else {
+            // continuation lines of scalars, lists and block scalars
+            while *i < lines.len() && (!is_content(lines[*i]) || indent_of(lines[*i]) > indent) {
+                *i += 1;
+            }
+            vec![]
+        };
+        let end = (start..*i)
+            .rev()
+            .find(|&l| is_content(lines[l]))
+            .map_or(start + 1, |l| l + 1);
+        if let Some((key, _)) = key_value {
+            nodes.push(Node {
+                key,
+                line: start,
+                indent,
+                end,
+                mapping,
+                children,
+            });
+        }
+    }
+    Ok(nodes)
+}
+fn parse(text: &str) -> Result<Vec<Node>, Error> {
+    let lines: Vec<&str> = text.lines().collect();
+    parse_block(&lines, &mut 0, None)
+}
+
+/// a `nl:` / `pt-BR:` / `zh_Hant:` style key
+fn is_locale(key: &str) -> bool {
+    let mut parts = key.split(['-', '_']);
+    parts
+        .next()
+        .is_some_and(|l| (2..=3).contains(&l.len()) && l.chars().all(|c| c.is_ascii_lowercase()))
+        && parts.all(|p| (2..=4).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+/// the nodes keys are resolved in, and the locale wrapper if there is one
+fn scope<'a>(nodes: &'a [Node], key: &str) -> (&'a [Node], Option<&'a Node>) {
+    match nodes {
+        [root] if root.mapping && is_locale(&root.key) => {
+            let explicit = key == root.key || key.starts_with(&format!("{}.", root.key));
+            if explicit {
+                (nodes, None)
+            } else {
+                (&root.children, Some(root))
+            }
+        }
+        _ => (nodes, None),
+    }
+}
+fn lookup<'a>(nodes: &'a [Node], path: &str) -> Option<(&'a [Node], &'a Node)> {
+    nodes.iter().find_map(|n| {
+        if n.key == path {
+            Some((nodes, n))
+        } else {
+            let rest = path.strip_prefix(&n.key)?.strip_prefix('.')?;
+            lookup(&n.children, rest)
+        }
+    })
+}
+fn find_node<'a>(nodes: &'a [Node], key: &str) -> Result<(&'a [Node], &'a Node), Error> {
+    let (nodes, _) = scope(nodes, key);
+    lookup(nodes, key).ok_or_else(|| Error::KeyNotFound(key.to_owned()))
+}
+/// the deepest existing mapping on `path` and the part of the path still missing
+fn deepest<'a, 'p>(nodes: &'a [Node], path: &'p str) -> (Option<&'a Node>, &'p str) {
+    nodes
+        .iter()
+        .find_map(|n| {
+            let rest = path.strip_prefix(&n.key)?.strip_prefix('.')?;
+            match deepest(&n.children, rest) {
+                (None, rest) => Some((Some(n), rest)),
+                found => Some(found),
+            }
+        })
+        .unwrap_or((None, path))
+}
+/// the indentation step used in the file, two spaces when it can't be told
+fn indent_unit(nodes: &[Node]) -> usize {
+    nodes
+        .iter()
+        .find_map(|n| Some(n.children.first()?.indent - n.indent))
+        .unwrap_or(2)
+}
+
+/// plain scalars that yaml 1.1 reads as another type: `0x1f`, `0o17`, `1_000`, `1:30`
+fn is_number_like(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    ["0x", "0o", "0b"].iter().any(|p| lower.starts_with(p))
+        || value.contains('_') && value.replace('_', "").parse::<f64>().is_ok()
+        || value.contains(':') && value.chars().all(|c| c.is_ascii_digit() || c == ':')
+}
+/// plain scalars when they read back as the same string, double quoted otherwise
+fn quote(value: &str) -> String {
+    let reserved = [
+        "true", "false", "yes", "no", "on", "off", "y", "n", "null", "~", ".inf", ".nan", "",
+    ];
+    let plain = !reserved.contains(&value.to_lowercase().as_str())
+        && value.parse::<f64>().is_err()
+        && !is_number_like(value)
+        && value.trim() == value
+        && !value.starts_with(|c: char| "!&*-?{}[]|>'\"%@`#,:".contains(c))
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.ends_with(':')
+        && !value.chars().any(|c| c.is_control());
+    if plain {
+        value.to_owned()
+    } else {
+        serde_json::to_string(value).expect("strings serialize")
+    }
+}
+fn quote_key(key: &str) -> String {
+    if key.contains(['.', ':', '#', ' ', '"', '\'']) || key.is_empty() {
+        serde_json::to_string(key).expect("strings serialize")
+    } else {
+        key.to_owned()
+    }
+}
+/// `key: value` lines where dots in `key` become nested mappings
+fn entry_lines(key: &str, value: &str, indent: usize, unit: usize) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    match key.split_once('.') {
+        None => vec![format!("{pad}{}: {}", quote_key(key), quote(value))],
+        Some((head, rest)) => {
+            let mut lines = vec![format!("{pad}{}:", quote_key(head))];
+            lines.extend(entry_lines(rest, value, indent + unit, unit));
+            lines
+        }
+    }
+}
+/// the lines of a flow scalar folded into one: a line break is a space, empty
+/// lines are line breaks and an escaped line break of a double quoted scalar
+/// disappears
+fn fold(value: &str, rest: &[&str]) -> String {
+    let mut folded = value.to_owned();
+    let mut breaks = 0;
+    for line in rest.iter().map(|l| l.trim()) {
+        if line.is_empty() {
+            breaks += 1;
+            continue;
+        }
+        let trimmed = folded.trim_end_matches([' ', '\t']);
+        let escapes = trimmed.len() - trimmed.trim_end_matches('\\').len();
+        if value.starts_with('"') && escapes % 2 == 1 && breaks == 0 {
+            folded.truncate(trimmed.len() - 1);
+        } else {
+            folded.truncate(trimmed.len());
+            match breaks {
+                0 => folded.push(' '),
+                _ => folded.push_str(&"\n".repeat(breaks)),
+            }
+        }
+        folded.push_str(line);
+        breaks = 0;
+    }
+    folded
+}
+/// the text of a double quoted scalar without its quotes, unescaped
+fn unescape_double(text: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        let hex = |chars: &mut std::str::Chars, len| {
+            let code: String = chars.take(len).collect();
+            u32::from_str_radix(&code, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("invalid escape \\{code}"))
+        };
+        match chars.next() {
+            Some('0') => out.push('\0'),
+            Some('a') => out.push('\x07'),
+            Some('b') => out.push('\x08'),
+            Some('t' | '\t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('v') => out.push('\x0b'),
+            Some('f') => out.push('\x0c'),
+            Some('r') => out.push('\r'),
+            Some('e') => out.push('\x1b'),
+            Some('N') => out.push('\u{85}'),
+            Some('_') => out.push('\u{a0}'),
+            Some('L') => out.push('\u{2028}'),
+            Some('P') => out.push('\u{2029}'),
+            Some('x') => out.push(hex(&mut chars, 2)?),
+            Some('u') => out.push(hex(&mut chars, 4)?),
+            Some('U') => out.push(hex(&mut chars, 8)?),
+            Some(c @ (' ' | '"' | '/' | '\\')) => out.push(c),
+            Some(c) => return Err(format!("invalid escape \\{c}")),
+            None => return Err("unterminated quoted scalar".to_owned()),
+        }
+    }
+    Ok(out)
+}
+/// the string a quoted scalar reads as, it may go on in `rest`
+fn quoted(value: &str, rest: &[&str]) -> Result<String, String> {
+    let folded = fold(value, rest);
+    let end = quoted_end(&folded).ok_or("unterminated quoted scalar")?;
+    let tail = folded[end..].trim_start();
+    if !tail.is_empty() && !tail.starts_with('#') {
+        return Err(format!("unexpected {tail:?} after the closing quote"));
+    }
+    let text = &folded[1..end - 1];
+    if value.starts_with('"') {
+        unescape_double(text)
+    } else {
+        Ok(text.replace("''", "'"))
+    }
+}
+/// the string a scalar value reads as, `rest` holds its continuation lines.
+/// flow collections, aliases and tagged values are left out.
+fn scalar(value: &str, rest: &[&str]) -> Result<Option<String>, String> {
+    if value.starts_with(['"', '\'']) {
+        return quoted(value, rest).map(Some);
+    }
+    let value = match trailing_comment(value) {
+        Some(comment) => value[..value.len() - comment.len()].trim_end(),
+        None => value,
+    };
+    let dedent = rest
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| indent_of(l))
+        .min()
+        .unwrap_or(0);
+    let block = rest
+        .iter()
+        .map(|l| l.get(dedent..).unwrap_or("").trim_end());
+    Ok(match value.chars().next() {
+        Some('|') => Some(block.collect::<Vec<_>>().join("\n")),
+        Some('>') => Some(block.collect::<Vec<_>>().join(" ")),
+        Some('[' | '{' | '&' | '*' | '!') => None,
+        _ => {
+            let mut words = vec![value];
+            words.extend(rest.iter().map(|l| l.trim()).filter(|l| !l.is_empty()));
+            Some(words.join(" "))
+        }
+    })
+}
+fn collect(
+    lines: &[&str],
+    nodes: &[Node],
+    prefix: &str,
+    out: &mut Vec<(String, String)>,
+) -> Result<(), Error> {
+    for node in nodes {
+        let key = format!("{prefix}{}", node.key);
+        if node.mapping {
+            collect(lines, &node.children, &format!("{key}."), out)?;
+            continue;
+        }
+        let line = lines[node.line];
+        let (_, colon) = split_key(line).expect("node lines have a key");
+        let value = scalar(value_of(line, colon), &lines[node.line + 1..node.end]);
+        let value = value.map_err(|msg| Error::Parse {
+            line: node.line + 1,
+            msg,
+        })?;
+        if let Some(value) = value {
+            out.push((key, value));
+        }
+    }
+    Ok(())
+}
+
+impl LangFormat for YamlFormat {
+    fn find(&self, text: &str, key: &str) -> Option<usize> {
+        let nodes = parse(text).ok()?;
+        find_node(&nodes, key).ok().map(|(_, n)| n.line)
+    }
+    fn insert(&self, text: &str, before: &str, key: &str, value: &str) -> Result<String, Error> {
+        let nodes = parse(text)?;
+        let (siblings, target) = find_node(&nodes, before)?;
+        let parent_path = &before[..before.len() - target.key.len()];
+        let name = key
+            .strip_prefix(parent_path)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| Error::OtherParent(key.to_owned(), before.to_owned()))?;
+        if siblings.iter().any(|n| n.key == name) {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        // comments right above the target stay with it
+        let lines: Vec<&str> = text.lines().collect();
+        let mut at = target.line;
+        while at > 0 && lines[at - 1].trim_start().starts_with('#') {
+            at -= 1;
+        }
+        let entry = format!(
+            "{}{}: {}",
+            " ".repeat(target.indent),
+            quote_key(name),
+            quote(value)
+        );
+        Ok(splice(text, at, 0, vec![entry]))
+    }
+    fn append(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let nodes = parse(text)?;
+        let unit = indent_unit(&nodes);
+        let (scoped, wrapper) = scope(&nodes, key);
+        if lookup(scoped, key).is_some() {
+            return Err(Error::KeyExists(key.to_owned()));
+        }
+        let (parent, missing) = match deepest(scoped, key) {
+            (None, missing) => (wrapper, missing),
+            found => found,
+        };
+        let (at, indent) = match parent {
+            Some(parent) if !parent.mapping => {
+                let existing = &key[..key.len() - missing.len() - 1];
+                return Err(Error::KeyExists(existing.to_owned()));
+            }
+            Some(parent) => {
+                let indent = parent
+                    .children
+                    .first()
+                    .map_or(parent.indent + unit, |c| c.indent);
+                (parent.end, indent)
+            }
+            None => {
+                let indent = scoped.first().map_or(0, |n| n.indent);
+                (
+                    scoped.last().map_or(text.lines().count(), |n| n.end),
+                    indent,
+                )
+            }
+        };
+        Ok(splice(
+            text,
+            at,
+            0,
+            entry_lines(missing, value, indent, unit),
+        ))
+    }
+    fn remove(&self, text: &str, key: &str) -> Result<String, Error> {
+        let nodes = parse(text)?;
+        let (_, target) = find_node(&nodes, key)?;
+        Ok(splice(text, target.line, target.end - target.line, vec![]))
+    }
+    fn replace(&self, text: &str, key: &str, value: &str) -> Result<String, Error> {
+        let nodes = parse(text)?;
+        let (_, target) = find_node(&nodes, key)?;
+        let line = text.lines().nth(target.line).expect("node line exists");
+        let (_, colon) = split_key(line).expect("node lines have a key");
+        let comment = (target.end == target.line + 1)
+            .then(|| trailing_comment(value_of(line, colon)))
+            .flatten()
+            .unwrap_or("");
+        let entry = format!("{} {}{comment}", &line[..=colon], quote(value));
+        Ok(splice(
+            text,
+            target.line,
+            target.end - target.line,
+            vec![entry],
+        ))
+    }
+    fn entries(&self, text: &str) -> Result<Vec<(String, String)>, Error> {
+        let lines: Vec<&str> = text.lines().collect();
+        let nodes = parse(text)?;
+        // keys of a locale wrapped file are listed without the locale
+        let (nodes, _) = scope(&nodes, "");
+        let mut out = vec![];
+        collect(&lines, nodes, "", &mut out)?;
+        Ok(out)
+    }
+}
@@ -1,6 +1,11 @@
-use crate::{file_handling, Input};
+use crate::format::line::Line;
+use crate::format::{csv, xliff, LangFormat};
+use crate::layout::Layout;
+use crate::serializer::ValueSerializer;
+use crate::{file_handling, format, translate, Input};
+use std::collections::hash_map::{Entry, HashMap};
 use std::fs::{self, canonicalize};
-use std::io;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -35,6 +40,7 @@ pub enum Action {
 pub struct RemoveConfig {
     pub verbose: bool,
     pub base_path: PathBuf,
+    pub layout: Layout,
     pub dst_tag: FindSpecified,
     pub languages: String,
     pub yes: bool,
@@ -43,6 +49,7 @@ pub struct RemoveConfig {
 pub struct FindConfig {
     pub verbose: bool,
     pub base_path: PathBuf,
+    pub layout: Layout,
     pub dst_tag: FindSpecified,
     pub languages: String,
 }
@@ -50,17 +57,88 @@ pub struct FindConfig {
 pub struct AppendConfig {
     pub verbose: bool,
     pub base_path: PathBuf,
+    pub layout: Layout,
     pub file: PathBuf,
     pub input: Input,
     pub src_tag: String,
+    pub serializer: Option<ValueSerializer>,
 }
 #[derive(Debug)]
 pub struct InsertConfig {
     pub verbose: bool,
     pub base_path: PathBuf,
+    pub layout: Layout,
     pub input: Input,
     pub src_tag: String,
     pub dst_tag: FindSpecified,
+    pub serializer: Option<ValueSerializer>,
+}
+#[derive(Debug)]
+pub struct ReplaceConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub input: Input,
+    pub dst_tag: FindSpecified,
+    pub serializer: Option<ValueSerializer>,
+}
+#[derive(Debug)]
+pub struct PoFillConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub template: PathBuf,
+    pub languages: String,
+    pub llm: translate::Llm,
+}
+#[derive(Debug)]
+pub struct ExportXliffConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub source: String,
+    pub target: String,
+    pub file: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub version: xliff::Version,
+    pub serializer: Option<ValueSerializer>,
+}
+#[derive(Debug)]
+pub struct ImportXliffConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub input: PathBuf,
+    pub serializer: Option<ValueSerializer>,
+}
+#[derive(Debug)]
+pub struct ExportCsvConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub languages: String,
+    pub file: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub serializer: Option<ValueSerializer>,
+}
+#[derive(Debug)]
+pub struct ImportCsvConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub input: PathBuf,
+    pub yes: bool,
+    pub serializer: Option<ValueSerializer>,
+}
+#[derive(Debug)]
+pub struct FmtConfig {
+    pub verbose: bool,
+    pub base_path: PathBuf,
+    pub layout: Layout,
+    pub file: Option<PathBuf>,
+    pub languages: String,
+    pub check: bool,
+    pub sections: bool,
 }
 #[derive(Error, Debug)]
 pub enum Error {
@@ -85,9 +163,16 @@ pub enum Error {
     NoSeparator,
     #[error("lang not found")]
     LangNoFound,
+    #[error("{0} file(s) not formatted")]
+    NotFormatted(usize),
+    #[error("{0} row(s) changed since the export")]
+    Conflicts(usize),
+    #[error("error: editing file {0}")]
+    Format(PathBuf, #[source] format::Error),
+    #[error(transparent)]
+    Translate(#[from] translate::Error),
 }
 type MyError = &'static str;
-// todo: add replace (one line support)
 // todo: add specify option
 pub fn run(config: Config) -> Result<(), MyError> {
     let config = if config.verbose { dbg!(config) } else { config };
@@ -97,7 +182,11 @@ pub fn run(config: Config) -> Result<(), MyError> {
         let file = config.input_file.unwrap();
         file_handling::read_from_file(file).map_err(|_| "something whent wrong reading input file")
     }?;
-    let language_texts = gen_language_text(&text, &config.new_var).map_err(|_| "lol")?;
+    let language_texts: Vec<(String, String)> = gen_language_text(&text)
+        .map_err(|_| "lol")?
+        .into_iter()
+        .map(|(lang, text)| (lang, ValueSerializer::Dotenv.entry(&config.new_var, &text)))
+        .collect();
     let langs: Vec<String> = language_texts
         .iter()
         .map(|(lang, _)| lang.to_string())
@@ -140,8 +229,8 @@ pub fn run(config: Config) -> Result<(), MyError> {
             }
         }
         Action::PrependFile(FindSpecified { needle, .. }) => {
-            let path_per_lang = language_base_find_file(config.base_path, &langs, &|path, _| {
-                find_line_occurance_in_file(path, &needle).map(|n| (path.to_owned(), n))
+            let path_per_lang = language_base_find_file(config.base_path, &langs, &|path, lang| {
+                find_line_occurance_in_file(path, lang, &needle).map(|n| (path.to_owned(), n))
             });
 
             for (lang, buff) in path_per_lang
@@ -157,8 +246,8 @@ pub fn run(config: Config) -> Result<(), MyError> {
             }
         }
         Action::Delete(FindSpecified { needle, .. }) => {
-            let path_per_lang = language_base_find_file(config.base_path, &langs, &|path, _| {
-                find_line_occurance_in_file(path, &needle).map(|n| (path.to_owned(), n))
+            let path_per_lang = language_base_find_file(config.base_path, &langs, &|path, lang| {
+                find_line_occurance_in_file(path, lang, &needle).map(|n| (path.to_owned(), n))
             });
 
             for (_, buff) in path_per_lang
@@ -172,74 +261,67 @@ pub fn run(config: Config) -> Result<(), MyError> {
     }
     Ok(())
 }
-pub fn find_line_occurance_in_file(path: impl AsRef<Path>, variable: &str) -> Option<usize> {
-    let s = fs::read_to_string(&path).ok()?;
-    find_line_occurance(&s, variable)
+pub fn find_line_occurance_in_file(
+    path: impl AsRef<Path>,
+    lang: &str,
+    variable: &str,
+) -> Option<usize> {
+    let (_, s) = file_handling::read_text_file(&path).ok()?;
+    format::for_path(&path, lang, None).find(&s, variable)
 }
 pub fn find_match<'a, T>(lang: &str, values: &'a [(String, T)]) -> Option<&'a T> {
     values.iter().find(|(l, _)| l == lang).map(|(_, t)| t)
 }
-pub fn process_language_text(line: &str, var_name: &str) -> Result<(String, String), Error> {
+pub fn process_language_text(line: &str) -> Result<(String, String), Error> {
     let (lang, text) = line.split_once(',').ok_or(Error::NoSeparator)?;
-    Ok((lang.to_string(), format!("{var_name}={text:?}")))
+    Ok((lang.to_string(), text.to_string()))
 }
-pub fn gen_language_text(text: &str, var_name: &str) -> Result<Vec<(String, String)>, Error> {
-    text.lines()
-        .map(|line| process_language_text(line, var_name))
-        .collect()
+/// splits `lang,translation` lines into (lang, translation) pairs
+pub fn gen_language_text(text: &str) -> Result<Vec<(String, String)>, Error> {
+    text.lines().map(process_language_text).collect()
 }
-fn find_line_occurance(text: &str, variable: &str) -> Option<usize> {
-    text.lines()
-        .enumerate()
-        .find_map(|(i, line)| line.starts_with(variable).then_some(i))
+/// applies `edit` to the text of `path`, keeping the style of the file
+fn edit_file<F>(path: &Path, edit: F) -> Result<(), Error>
+where
+    F: FnOnce(&str) -> Result<String, format::Error>,
+{
+    let (style, text) = file_handling::read_text_file(path)?;
+    let text = edit(&text).map_err(|e| Error::Format(path.to_owned(), e))?;
+    file_handling::write_text_file(path, style, &text)?;
+    Ok(())
 }
-
-fn append_to_file(path: impl AsRef<Path>, value: &str) -> io::Result<()> {
-    let s: String = fs::read_to_string(&path)?;
-    let inter = if s == "" || s.ends_with("\n") {
+fn append_to_file(path: impl AsRef<Path>, value: &str) -> Result<(), file_handling::Error> {
+    let (style, s) = file_handling::read_text_file(&path)?;
+    let inter = if s.is_empty() || s.ends_with('\n') {
         ""
     } else {
         "\n"
     };
     let s = s + inter + value;
-    fs::write(&path, &s)?;
-    Ok(())
+    file_handling::write_text_file(&path, style, &s)
 }
-fn insert_file_at_line(path: impl AsRef<Path>, value: &str, index: usize) -> io::Result<()> {
-    let s: String = fs::read_to_string(&path)?;
-    let s = if s.len() == 0 {
-        value.to_string()
-    } else if index == 0 {
-        value.to_owned() + "\n" + &s
-    } else {
-        s.lines()
-            .enumerate()
-            .map(|(i, l)| {
-                if i == index - 1 {
-                    l.to_owned() + "\n" + value
-                } else {
-                    l.to_owned()
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
-    };
-    fs::write(&path, &s)?;
-    Ok(())
+fn insert_file_at_line(
+    path: impl AsRef<Path>,
+    value: &str,
+    index: usize,
+) -> Result<(), file_handling::Error> {
+    let (style, s) = file_handling::read_text_file(&path)?;
+    let mut lines: Vec<&str> = s.lines().collect();
+    lines.insert(index.min(lines.len()), value);
+    file_handling::write_text_file(&path, style, &lines.join("\n"))
 }
-fn delete_line(path: impl AsRef<Path>, index: usize) -> io::Result<()> {
-    let s: String = fs::read_to_string(&path)?;
-    let s = s
+fn delete_line(path: impl AsRef<Path>, index: usize) -> Result<(), file_handling::Error> {
+    let (style, s) = file_handling::read_text_file(&path)?;
+    let lines: Vec<&str> = s
         .lines()
         .enumerate()
-        .filter_map(|(i, l)| (i != index).then(|| l.to_string()))
-        .collect::<Vec<String>>()
-        .join("\n");
-    fs::write(&path, &s)?;
-    Ok(())
+        .filter(|(i, _)| *i != index)
+        .map(|(_, l)| l)
+        .collect();
+    file_handling::write_text_file(&path, style, &lines.join("\n"))
 }
-fn print_line(path: impl AsRef<Path>, index: usize) -> io::Result<()> {
-    let s: String = fs::read_to_string(&path)?;
+fn print_line(path: impl AsRef<Path>, index: usize) -> Result<(), file_handling::Error> {
+    let (_, s) = file_handling::read_text_file(&path)?;
     for (i, line) in s.lines().enumerate() {
         if i == index {
             println!("{index}: {line}");
@@ -269,44 +351,40 @@ where
 
 fn general_find(
     base: impl AsRef<Path>,
+    layout: &Layout,
     langs: &[&str],
     file: Option<&Path>,
     tag: Option<&str>,
 ) -> Vec<(String, Result<FileSearchResult, Error>)> {
-    let base = base.as_ref().to_owned();
+    let base = base.as_ref();
     langs
         .iter()
         .map(|lang| {
-            let mut base = base.clone();
-            base.push(lang);
-            let canon_file = file.and_then(|f| canonicalize(PathBuf::from_iter([&base, f])).ok());
-            let predicate = |path: &Path| {
-                //eprintln!("path: {:?}\nfile: {canon_file:?}", canonicalize(path).ok());
-
-                let out_file = match (canonicalize(path).ok().as_ref(), &canon_file) {
-                    (_, None) => None,
-                    (None, Some(_)) => panic!("can't canonacalize file"),
-                    (Some(path), Some(file)) => Some(path == file),
-                };
-                match (tag, out_file) {
-                    (_, Some(false)) => None,
-                    (None, Some(true)) => Some(FileSearchResult {
+            let search = |path: &Path| match tag {
+                Some(tag) => {
+                    find_line_occurance_in_file(path, lang, tag).map(|n| FileSearchResult {
                         file: path.to_owned(),
-                        line: None,
-                    }),
-                    (Some(tag), _) => {
-                        find_line_occurance_in_file(path, tag).map(|n| FileSearchResult {
-                            file: path.to_owned(),
-                            line: Some(n),
-                        })
-                    }
-                    (None, None) => panic!("no file or tag find given"),
+                        line: Some(n),
+                    })
                 }
+                None => path.is_file().then(|| FileSearchResult {
+                    file: path.to_owned(),
+                    line: None,
+                }),
+            };
+            // the file itself or, when the layout needs one and none is given,
+            // every file of the language
+            let found = match layout.path(base, lang, file) {
+                Some(path) => search(&path),
+                None => layout
+                    .files(base, lang)
+                    .into_iter()
+                    .find_map(|(_, path)| search(&path)),
             };
             (
                 lang.to_string(),
-                file_handling::find_file(&base, &predicate).ok_or(Error::TagSearchFailed {
-                    base,
+                found.ok_or_else(|| Error::TagSearchFailed {
+                    base: base.to_owned(),
                     tag: tag.map(ToOwned::to_owned),
                     file: file.map(ToOwned::to_owned),
                     language: lang.to_string(),
@@ -315,6 +393,58 @@ fn general_find(
         })
         .collect()
 }
+/// where `file` (relative to the language) lives for `lang`
+fn lang_path(layout: &Layout, base: &Path, lang: &str, file: &Path) -> PathBuf {
+    layout.path(base, lang, Some(file)).expect("file given")
+}
+/// sets every key to its value, missing keys are appended. also returns how many changed
+fn set_values(
+    lang_format: &dyn LangFormat,
+    text: &str,
+    values: &[(String, String)],
+) -> Result<(String, usize), format::Error> {
+    let existing = lang_format.entries(text)?;
+    let mut text = text.to_owned();
+    let mut changed = 0;
+    for (key, value) in values {
+        text = match find_match(key, &existing) {
+            Some(current) if current == value => continue,
+            Some(_) => lang_format.replace(&text, key, value)?,
+            None => lang_format.append(&text, key, value)?,
+        };
+        changed += 1;
+    }
+    Ok((text, changed))
+}
+/// the text of `path`, the empty text of its format when the file doesn't exist yet
+fn read_or_empty(path: &Path) -> Result<(file_handling::TextStyle, String), Error> {
+    if path.exists() {
+        return Ok(file_handling::read_text_file(path)?);
+    }
+    let text = format::FileFormat::from_path(path).empty_text();
+    Ok((file_handling::TextStyle::default(), text.to_owned()))
+}
+/// writes `text` to `path`, creating the directories of a new language
+fn write_lang_file(path: &Path, style: file_handling::TextStyle, text: &str) -> Result<(), Error> {
+    if let Some(dir) = path.parent().filter(|d| !d.exists()) {
+        fs::create_dir_all(dir)?;
+    }
+    Ok(file_handling::write_text_file(path, style, text)?)
+}
+/// the entries of `lang` in `path`, none when the file doesn't exist
+fn read_entries(
+    path: &Path,
+    lang: &str,
+    serializer: Option<ValueSerializer>,
+) -> Result<Vec<(String, String)>, Error> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let (_, text) = file_handling::read_text_file(path)?;
+    format::for_path(path, lang, serializer)
+        .entries(&text)
+        .map_err(|e| Error::Format(path.to_owned(), e))
+}
 pub fn append(config: AppendConfig) -> Result<(), Error> {
     if config.verbose {
         dbg!(&config);
@@ -325,7 +455,7 @@ pub fn append(config: AppendConfig) -> Result<(), Error> {
         Input::File(file) => file_handling::read_from_file(&file)?,
     };
     // extract language texts
-    let language_texts = gen_language_text(&text, &config.src_tag)?;
+    let language_texts = gen_language_text(&text)?;
 
     // extract languages
     let languages: Vec<&str> = language_texts
@@ -334,7 +464,13 @@ pub fn append(config: AppendConfig) -> Result<(), Error> {
         .collect();
 
     // find general (file and / or needle)
-    let path_per_lang = general_find(config.base_path, &languages, Some(&config.file), None);
+    let path_per_lang = general_find(
+        config.base_path,
+        &config.layout,
+        &languages,
+        Some(&config.file),
+        None,
+    );
 
     // additional post processing
     let path_per_lang = path_per_lang
@@ -347,8 +483,11 @@ pub fn append(config: AppendConfig) -> Result<(), Error> {
         if config.verbose {
             eprintln!("appending to file: {:?}", &search_find.file);
         }
-        let replacement_text = find_match(&lang, &language_texts).ok_or(Error::LangNoFound)?;
-        append_to_file(&search_find.file, replacement_text)?;
+        let translation = find_match(&lang, &language_texts).ok_or(Error::LangNoFound)?;
+        let lang_format = format::for_path(&search_find.file, &lang, config.serializer);
+        edit_file(&search_find.file, |text| {
+            lang_format.append(text, &config.src_tag, translation)
+        })?;
     }
     Ok(())
 }
@@ -363,6 +502,7 @@ pub fn remove(config: RemoveConfig) -> Result<(), Error> {
     // find general (file and / or needle)
     let path_per_lang = general_find(
         config.base_path,
+        &config.layout,
         &languages,
         config.dst_tag.file.as_deref(),
         Some(&config.dst_tag.needle),
@@ -375,13 +515,15 @@ pub fn remove(config: RemoveConfig) -> Result<(), Error> {
         .collect::<Result<Vec<(String, FileSearchResult)>, Error>>()?;
 
     // action remove
-    for (_, search_find) in path_per_lang {
+    for (lang, search_find) in path_per_lang {
         let index = search_find.line.expect("general_find with needle");
         if config.verbose {
             eprintln!("removing line: {index} from file: {:?}", &search_find.file);
         }
-
-        delete_line(&search_find.file, index)?
+        let lang_format = format::for_path(&search_find.file, &lang, None);
+        edit_file(&search_find.file, |text| {
+            lang_format.remove(text, &config.dst_tag.needle)
+        })?;
     }
     Ok(())
 }
@@ -395,6 +537,7 @@ pub fn find(config: FindConfig) -> Result<(), Error> {
     // find general (file and / or needle)
     let path_per_lang = general_find(
         config.base_path,
+        &config.layout,
         &languages,
         config.dst_tag.file.as_deref(),
         Some(&config.dst_tag.needle),
@@ -411,7 +554,7 @@ pub fn find(config: FindConfig) -> Result<(), Error> {
         let index = search_find.line.expect("general_find with needle");
         eprintln!("lang: {lang}, path: {}", search_find.file.to_str().unwrap());
         print_line(&search_find.file, index)?;
-        eprintln!("");
+        eprintln!();
     }
     Ok(())
 }
@@ -425,7 +568,7 @@ pub fn insert(config: InsertConfig) -> Result<(), Error> {
         Input::File(file) => file_handling::read_from_file(&file)?,
     };
     // extract language texts
-    let language_texts = gen_language_text(&text, &config.src_tag)?;
+    let language_texts = gen_language_text(&text)?;
 
     // extract languages
     let languages: Vec<&str> = language_texts
@@ -436,6 +579,7 @@ pub fn insert(config: InsertConfig) -> Result<(), Error> {
     // find general (file and / or needle)
     let path_per_lang = general_find(
         config.base_path,
+        &config.layout,
         &languages,
         config.dst_tag.file.as_deref(),
         Some(&config.dst_tag.needle),
@@ -447,17 +591,713 @@ pub fn insert(config: InsertConfig) -> Result<(), Error> {
         .map(|(lang, result)| Ok((lang, result?)))
         .collect::<Result<Vec<(String, FileSearchResult)>, Error>>()?;
 
-    // action appand
+    // action insert
     for (lang, search_find) in path_per_lang {
-        let index = search_find.line.expect("general_find with needle");
         if config.verbose {
-            eprintln!("appending to file: {:?}", &search_find.file);
+            eprintln!("inserting in file: {:?}", &search_find.file);
+        }
+        let translation = find_match(&lang, &language_texts).ok_or(Error::LangNoFound)?;
+        let lang_format = format::for_path(&search_find.file, &lang, config.serializer);
+        edit_file(&search_find.file, |text| {
+            lang_format.insert(text, &config.dst_tag.needle, &config.src_tag, translation)
+        })?;
+    }
+    Ok(())
+}
+pub fn replace(config: ReplaceConfig) -> Result<(), Error> {
+    // (extract text)
+    let text = match config.input {
+        Input::Text(text) => text,
+        Input::File(file) => file_handling::read_from_file(&file)?,
+    };
+    // extract language texts
+    let language_texts = gen_language_text(&text)?;
+
+    // extract languages
+    let languages: Vec<&str> = language_texts
+        .iter()
+        .map(|(lang, _)| lang.as_ref())
+        .collect();
+
+    // find general (file and / or needle)
+    let path_per_lang = general_find(
+        config.base_path,
+        &config.layout,
+        &languages,
+        config.dst_tag.file.as_deref(),
+        Some(&config.dst_tag.needle),
+    );
+
+    // additional post processing
+    let path_per_lang = path_per_lang
+        .into_iter()
+        .map(|(lang, result)| Ok((lang, result?)))
+        .collect::<Result<Vec<(String, FileSearchResult)>, Error>>()?;
+
+    // action replace
+    for (lang, search_find) in path_per_lang {
+        if config.verbose {
+            eprintln!("replacing in file: {:?}", &search_find.file);
+        }
+        let translation = find_match(&lang, &language_texts).ok_or(Error::LangNoFound)?;
+        let lang_format = format::for_path(&search_find.file, &lang, config.serializer);
+        edit_file(&search_find.file, |text| {
+            lang_format.replace(text, &config.dst_tag.needle, translation)
+        })?;
+    }
+    Ok(())
+}
+pub fn fmt(config: FmtConfig) -> Result<(), Error> {
+    // extract languages
+    let languages: Vec<&str> = config.languages.split(",").collect();
+
+    // find files (all of them when no file given)
+    let mut files = vec![];
+    for lang in languages {
+        match config
+            .layout
+            .path(&config.base_path, lang, config.file.as_deref())
+        {
+            Some(path) => {
+                if !path.is_file() {
+                    return Err(Error::TagSearchFailed {
+                        base: config.base_path,
+                        tag: None,
+                        file: config.file,
+                        language: lang.to_string(),
+                    });
+                }
+                files.push(path);
+            }
+            None => files.extend(
+                config
+                    .layout
+                    .files(&config.base_path, lang)
+                    .into_iter()
+                    .map(|(_, path)| path),
+            ),
+        }
+    }
+
+    // action format
+    let mut unformatted = 0;
+    for file in files {
+        if format::FileFormat::from_path(&file) != format::FileFormat::Line {
+            if config.verbose {
+                eprintln!("skipping file: {:?}", &file);
+            }
+            continue;
+        }
+        let raw = file_handling::read_from_file(&file)?;
+        let (style, text) = file_handling::TextStyle::normalize(&raw);
+        // always end with a newline, the bom and line endings are kept
+        let style = file_handling::TextStyle {
+            final_newline: true,
+            ..style
+        };
+        let formatted =
+            format_lang_text(&text, config.sections).map_err(|e| Error::Format(file.clone(), e))?;
+        let formatted = style.apply(&formatted);
+        if formatted == raw {
+            continue;
+        }
+        if config.check {
+            eprintln!("not formatted: {}", file.display());
+            unformatted += 1;
+        } else {
+            if config.verbose {
+                eprintln!("formatting file: {:?}", &file);
+            }
+            file_handling::write_to_file(&file, &formatted)?;
+        }
+    }
+    if unformatted > 0 {
+        return Err(Error::NotFormatted(unformatted));
+    }
+    Ok(())
+}
+
+pub fn po_fill(config: PoFillConfig) -> Result<(), Error> {
+    // extract languages
+    let languages: Vec<&str> = config.languages.split(",").collect();
+
+    // find files, missing ones are created from the template
+    let (style, template) = file_handling::read_text_file(&config.template)?;
+    let name = config.template.with_extension("po");
+    let name = Path::new(name.file_name().unwrap_or_default());
+    // gettext's own layout unless another one is configured
+    let layout = match config.layout {
+        layout if layout == Layout::default() => "{base}/{lang}/LC_MESSAGES/{file}"
+            .parse()
+            .expect("valid layout"),
+        layout => layout,
+    };
+    let mut path_per_lang = vec![];
+    for lang in languages {
+        let path = lang_path(&layout, &config.base_path, lang, name);
+        if !path.exists() {
+            if config.verbose {
+                eprintln!("creating file: {:?}", &path);
+            }
+            if format::po::plural_forms(lang).is_none() {
+                eprintln!("warning: no plural forms known for {lang}, kept those of the template");
+            }
+            let text = format::po::from_template(&template, lang)
+                .map_err(|e| Error::Format(config.template.clone(), e))?;
+            fs::create_dir_all(path.parent().expect("joined path"))?;
+            file_handling::write_text_file(&path, style, &text)?;
+        }
+        path_per_lang.push((lang.to_string(), path));
+    }
+
+    if config.llm.check_model {
+        config.llm.check_model()?;
+    }
+
+    // collect untranslated entries with the languages they miss, fuzzy ones
+    // wait for a translator
+    let mut untranslated: Vec<(format::po::Entry, Vec<String>)> = vec![];
+    for (lang, path) in &path_per_lang {
+        let (_, text) = file_handling::read_text_file(path)?;
+        let entries = format::po::parse(&text).map_err(|e| Error::Format(path.to_owned(), e))?;
+        for entry in entries {
+            if entry.is_header() || entry.is_translated() || entry.is_fuzzy() {
+                continue;
+            }
+            match untranslated
+                .iter_mut()
+                .find(|(e, _)| e.key() == entry.key())
+            {
+                Some((_, langs)) => langs.push(lang.to_string()),
+                None => untranslated.push((entry, vec![lang.to_string()])),
+            }
         }
-        let replacement_text = find_match(&lang, &language_texts).ok_or(Error::LangNoFound)?;
-        insert_file_at_line(&search_find.file, replacement_text, index)?;
+    }
+
+    // action fill
+    let total = untranslated.len();
+    for (filled, (entry, langs)) in untranslated.into_iter().enumerate() {
+        let translated = po_translate(&config.llm, &entry, &langs, config.verbose);
+        let (singular, plural) = match translated {
+            Ok(translated) => translated,
+            // what is filled stays, the rest waits for the next run
+            Err(e) => {
+                eprintln!("stopped after filling {filled} of {total} entries");
+                if config.llm.stats {
+                    config.llm.meter.print_stats(&config.llm.model);
+                }
+                return Err(e);
+            }
+        };
+        for lang in langs {
+            let path = find_match(&lang, &path_per_lang).expect("collected per language");
+            let singular = find_match(&lang, &singular).ok_or(Error::LangNoFound)?;
+            let plural = find_match(&lang, &plural).unwrap_or(singular);
+            if config.verbose {
+                eprintln!("filling {:?} in file: {:?}", entry.key(), path);
+            }
+            edit_file(path, |text| {
+                format::po::fill(text, &entry.key(), singular, plural)
+            })?;
+        }
+    }
+    if config.llm.stats {
+        config.llm.meter.print_stats(&config.llm.model);
     }
     Ok(())
 }
+/// (lang, translation) pairs
+type Translations = Vec<(String, String)>;
+/// the translations of the singular and plural of `entry` per language
+fn po_translate(
+    llm: &translate::Llm,
+    entry: &format::po::Entry,
+    langs: &[String],
+    verbose: bool,
+) -> Result<(Translations, Translations), Error> {
+    let singular = llm.translate(&entry.msgid, &langs.join(","), verbose)?;
+    let singular = gen_language_text(&singular)?;
+    let plural = match &entry.msgid_plural {
+        Some(plural) => gen_language_text(&llm.translate(plural, &langs.join(","), verbose)?)?,
+        None => vec![],
+    };
+    Ok((singular, plural))
+}
+pub fn export_xliff(config: ExportXliffConfig) -> Result<(), Error> {
+    // collect the units per file of the source language
+    let mut files = vec![];
+    for (file, source_path) in config.layout.files(&config.base_path, &config.source) {
+        if config.file.as_ref().is_some_and(|f| *f != file) {
+            continue;
+        }
+        if config.verbose {
+            eprintln!("exporting file: {:?}", &source_path);
+        }
+        let source = read_entries(&source_path, &config.source, config.serializer)?;
+        let target_path = lang_path(&config.layout, &config.base_path, &config.target, &file);
+        let target = read_entries(&target_path, &config.target, config.serializer)?;
+        let units = source
+            .into_iter()
+            .map(|(key, source)| {
+                let target = find_match(&key, &target).filter(|t| !t.is_empty());
+                xliff::Unit {
+                    target: target.cloned(),
+                    key,
+                    source,
+                }
+            })
+            .collect();
+        files.push(xliff::File {
+            original: file.to_string_lossy().replace('\\', "/"),
+            units,
+        });
+    }
+    if files.is_empty() {
+        return Err(Error::TagSearchFailed {
+            base: config.base_path,
+            tag: None,
+            file: config.file,
+            language: config.source,
+        });
+    }
+
+    // action export
+    let doc = xliff::Document {
+        version: config.version,
+        source_language: config.source,
+        target_language: config.target,
+        files,
+    };
+    let text = xliff::write(&doc);
+    match config.output {
+        Some(path) => file_handling::write_to_file(&path, &text)?,
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+pub fn import_xliff(config: ImportXliffConfig) -> Result<(), Error> {
+    let (_, text) = file_handling::read_text_file(&config.input)?;
+    let doc = xliff::read(&text).map_err(|e| Error::Format(config.input.clone(), e))?;
+    if doc.source_language.is_empty() || doc.target_language.is_empty() {
+        return Err(Error::LangNoFound);
+    }
+
+    // action import, units whose source changed since the export are skipped
+    let mut imported = 0;
+    let mut changed = 0;
+    for file in &doc.files {
+        let file_path = PathBuf::from(&file.original);
+        let source_path = lang_path(
+            &config.layout,
+            &config.base_path,
+            &doc.source_language,
+            &file_path,
+        );
+        let source = read_entries(&source_path, &doc.source_language, config.serializer)?;
+        let mut updates = vec![];
+        for unit in &file.units {
+            let Some(target) = unit.target.as_ref().filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            match find_match(&unit.key, &source) {
+                Some(current) if *current == unit.source => {
+                    updates.push((unit.key.clone(), target.clone()))
+                }
+                Some(_) => {
+                    eprintln!(
+                        "source changed since export: {} {}",
+                        file.original, unit.key
+                    );
+                    changed += 1;
+                }
+                None => {
+                    eprintln!("no longer in source: {} {}", file.original, unit.key);
+                    changed += 1;
+                }
+            }
+        }
+        if updates.is_empty() {
+            continue;
+        }
+        let target_path = lang_path(
+            &config.layout,
+            &config.base_path,
+            &doc.target_language,
+            &file_path,
+        );
+        if config.verbose {
+            eprintln!("importing in file: {:?}", &target_path);
+        }
+        let lang_format = format::for_path(&target_path, &doc.target_language, config.serializer);
+        let (style, text) = read_or_empty(&target_path)?;
+        let (text, changed) = set_values(&*lang_format, &text, &updates)
+            .map_err(|e| Error::Format(target_path.clone(), e))?;
+        imported += changed;
+        write_lang_file(&target_path, style, &text)?;
+    }
+    eprintln!("imported {imported} unit(s), skipped {changed} with a changed source");
+    Ok(())
+}
+pub fn export_csv(config: ExportCsvConfig) -> Result<(), Error> {
+    // extract languages
+    let languages: Vec<&str> = config.languages.split(",").collect();
+
+    // the files of all languages, in the order they are first seen
+    let mut files: Vec<PathBuf> = vec![];
+    for lang in &languages {
+        for (file, _) in config.layout.files(&config.base_path, lang) {
+            if !files.contains(&file) && config.file.as_ref().is_none_or(|f| *f == file) {
+                files.push(file);
+            }
+        }
+    }
+    if files.is_empty() {
+        return Err(Error::TagSearchFailed {
+            base: config.base_path,
+            tag: None,
+            file: config.file,
+            language: config.languages,
+        });
+    }
+
+    // one row per key, the checksum lets the import notice later edits of the files
+    let mut header = vec!["file", "key"];
+    header.extend(&languages);
+    header.push("checksum");
+    let mut lines = vec![csv::write_row(&header, ',')];
+    for file in files {
+        if config.verbose {
+            eprintln!("exporting file: {:?}", &file);
+        }
+        let entries = languages
+            .iter()
+            .map(|lang| {
+                read_entries(
+                    &lang_path(&config.layout, &config.base_path, lang, &file),
+                    lang,
+                    config.serializer,
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut keys: Vec<&String> = vec![];
+        for (key, _) in entries.iter().flatten() {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        let name = file.to_string_lossy().replace('\\', "/");
+        for key in keys {
+            let values: Vec<&str> = entries
+                .iter()
+                .map(|e| find_match(key, e).map_or("", String::as_str))
+                .collect();
+            let sum = checksum(&values);
+            let mut row = vec![name.as_str(), key];
+            row.extend(&values);
+            row.push(&sum);
+            lines.push(csv::write_row(&row, ','));
+        }
+    }
+
+    // action export
+    let text = lines.join("\n") + "\n";
+    match config.output {
+        Some(path) => file_handling::write_to_file(&path, &text)?,
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+/// a file with the language to edit in it
+type LangFile<'a> = (PathBuf, &'a str);
+pub fn import_csv(config: ImportCsvConfig) -> Result<(), Error> {
+    let (_, text) = file_handling::read_text_file(&config.input)?;
+    let parse_error = |msg: &str| {
+        let error = format::Error::Parse {
+            line: 1,
+            msg: msg.to_owned(),
+        };
+        Error::Format(config.input.clone(), error)
+    };
+    let rows = csv::parse(&text, csv::delimiter(&text))
+        .map_err(|e| Error::Format(config.input.clone(), e))?;
+    let mut rows = rows.into_iter();
+    let header = rows.next().unwrap_or_default();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(file_column), Some(key_column)) = (column("file"), column("key")) else {
+        return Err(parse_error("expected a file and a key column"));
+    };
+    let checksum_column = column("checksum");
+    let languages: Vec<(usize, &str)> = header
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != file_column && i != key_column && Some(i) != checksum_column)
+        .map(|(i, lang)| (i, lang.as_str()))
+        .collect();
+
+    // collect the changes per file and language, rows edited in the files since the
+    // export conflict. a string catalog holds several languages in one file
+    let mut current: HashMap<LangFile, Vec<(String, String)>> = HashMap::new();
+    let mut changes: Vec<(LangFile, Vec<(String, String)>)> = vec![];
+    let mut conflicts = 0;
+    for row in rows {
+        let cell = |i: usize| row.get(i).map_or("", String::as_str);
+        let file = PathBuf::from(cell(file_column));
+        let key = cell(key_column);
+        if key.is_empty() {
+            continue;
+        }
+        for &(_, lang) in &languages {
+            let path = lang_path(&config.layout, &config.base_path, lang, &file);
+            if let Entry::Vacant(slot) = current.entry((path, lang)) {
+                let entries = read_entries(&slot.key().0, lang, config.serializer)?;
+                slot.insert(entries);
+            }
+        }
+        let mut values = vec![];
+        let mut row_changes = vec![];
+        for &(i, lang) in &languages {
+            let path = lang_path(&config.layout, &config.base_path, lang, &file);
+            let value = find_match(key, &current[&(path.clone(), lang)]).map_or("", String::as_str);
+            // empty cells leave the value as it is
+            if !cell(i).is_empty() && cell(i) != value {
+                row_changes.push(((path, lang), cell(i).to_owned()));
+            }
+            values.push(value);
+        }
+        if row_changes.is_empty() {
+            continue;
+        }
+        if checksum_column.is_some_and(|c| cell(c) != checksum(&values)) {
+            eprintln!("conflict, changed since export: {} {key}", file.display());
+            conflicts += 1;
+            continue;
+        }
+        for (file, value) in row_changes {
+            match changes.iter_mut().find(|(f, _)| *f == file) {
+                Some((_, values)) => values.push((key.to_owned(), value)),
+                None => changes.push((file, vec![(key.to_owned(), value)])),
+            }
+        }
+    }
+
+    // preview, the languages of a file are applied one after the other
+    let mut edited: Vec<(PathBuf, file_handling::TextStyle, String, String)> = vec![];
+    for ((path, lang), values) in changes {
+        let at = match edited.iter().position(|(p, ..)| *p == path) {
+            Some(at) => at,
+            None => {
+                let (style, text) = read_or_empty(&path)?;
+                edited.push((path.clone(), style, text.clone(), text));
+                edited.len() - 1
+            }
+        };
+        let lang_format = format::for_path(&path, lang, config.serializer);
+        let (new_text, _) = set_values(&*lang_format, &edited[at].3, &values)
+            .map_err(|e| Error::Format(path.clone(), e))?;
+        edited[at].3 = new_text;
+    }
+    for (path, _, text, new_text) in &edited {
+        print_diff(path, text, new_text);
+    }
+
+    // action import
+    if edited.is_empty() {
+        eprintln!("nothing to import");
+    } else if config.yes || confirm("apply these changes?")? {
+        for (path, style, _, text) in edited {
+            if config.verbose {
+                eprintln!("importing in file: {:?}", &path);
+            }
+            write_lang_file(&path, style, &text)?;
+        }
+    }
+    if conflicts > 0 {
+        return Err(Error::Conflicts(conflicts));
+    }
+    Ok(())
+}
+/// fnv-1a over the values of a row, stable across builds
+fn checksum(values: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in values.iter().flat_map(|v| v.bytes().chain([0x1f])) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+/// asks on the terminal, no when there is no terminal to ask on
+fn confirm(question: &str) -> Result<bool, Error> {
+    if !io::stdin().is_terminal() {
+        eprintln!("run with --yes to apply");
+        return Ok(false);
+    }
+    eprint!("{question} [y/N] ");
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+/// the lines of `old` and `new` marked ' ', '-' or '+' along their longest common subsequence
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(char, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut out = vec![];
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            out.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push(('-', old[i]));
+            i += 1;
+        } else {
+            out.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+/// prints the changed lines of a file, with the line they start at
+fn print_diff(path: &Path, old: &str, new: &str) {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // only the part between the common start and end needs diffing
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    println!("--- {}", path.display());
+    println!("+++ {}", path.display());
+    let mut line = prefix + 1;
+    let mut in_hunk = false;
+    for (sign, text) in diff_lines(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    ) {
+        if sign == ' ' {
+            in_hunk = false;
+            line += 1;
+            continue;
+        }
+        if !in_hunk {
+            println!("@@ line {line} @@");
+            in_hunk = true;
+        }
+        println!("{sign}{text}");
+        if sign == '-' {
+            line += 1;
+        }
+    }
+}
+#[derive(Debug, Default)]
+struct FmtSection {
+    header: Vec<String>,
+    entries: Vec<FmtEntry>,
+}
+#[derive(Debug)]
+struct FmtEntry {
+    comments: Vec<String>,
+    key: String,
+    value: String,
+}
+/// rewrites a `KEY="value"` file with sorted keys, normalized quoting,
+/// no duplicate blank lines and a single trailing newline. quoted values may
+/// span lines, a value whose quote is never closed fails the whole file.
+///
+/// `sections` keeps comment-delimited blocks in place and only sorts inside them,
+/// otherwise comments move along with the key that follows them. comments at the
+/// start of the file followed by a blank line are its header and stay on top.
+pub fn format_lang_text(text: &str, sections: bool) -> Result<String, format::Error> {
+    let mut header: Vec<String> = vec![];
+    let mut parsed: Vec<FmtSection> = vec![FmtSection::default()];
+    let mut comments: Vec<String> = vec![];
+    let mut seen_entry = false;
+    for line in format::line::parse(text, &['='])? {
+        let (key, value) = match line {
+            Line::Entry { key, value, .. } => (key, value),
+            Line::Other(line) if line.trim().is_empty() => {
+                if !sections && !seen_entry {
+                    header.append(&mut comments);
+                }
+                continue;
+            }
+            Line::Other(line) => {
+                let line = line.trim_end().to_string();
+                if !sections {
+                    comments.push(line);
+                } else if let Some(section) = parsed.last_mut().filter(|s| s.entries.is_empty()) {
+                    section.header.push(line);
+                } else {
+                    parsed.push(FmtSection {
+                        header: vec![line],
+                        entries: vec![],
+                    });
+                }
+                continue;
+            }
+        };
+        seen_entry = true;
+        let section = parsed.last_mut().expect("starts with one section");
+        section.entries.push(FmtEntry {
+            comments: std::mem::take(&mut comments),
+            key: key.to_string(),
+            value: normalize_value(&value),
+        });
+    }
+    let mut blocks: Vec<Vec<String>> = vec![];
+    if !header.is_empty() {
+        blocks.push(header);
+    }
+    for mut section in parsed {
+        section.entries.sort_by(|a, b| a.key.cmp(&b.key));
+        let mut block = section.header;
+        for entry in section.entries {
+            block.extend(entry.comments);
+            block.push(format!("{}={}", entry.key, entry.value));
+        }
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+    }
+    // comments after the last key
+    if !comments.is_empty() {
+        blocks.push(comments);
+    }
+    let mut out = blocks
+        .into_iter()
+        .map(|block| block.join("\n"))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    Ok(out)
+}
+fn normalize_value(value: &str) -> String {
+    let quoted = |q: char| value.len() >= 2 && value.starts_with(q) && value.ends_with(q);
+    if quoted('"') {
+        value.to_string()
+    } else {
+        let inner = if quoted('\'') {
+            &value[1..value.len() - 1]
+        } else {
+            value
+        };
+        ValueSerializer::Dotenv.quote(inner)
+    }
+}
 // general flow
 // (extract text)
 // (extract language texts)
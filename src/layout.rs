@@ -0,0 +1,247 @@
+use crate::file_handling;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("layout {0:?} has no {{lang}}")]
+    NoLang(String),
+    #[error("layout {0:?} has more than one {{file}}")]
+    ManyFiles(String),
+    #[error("layout {0:?} has to start with {{base}}")]
+    BaseNotFirst(String),
+    #[error("unknown placeholder {{{1}}} in layout {0:?}")]
+    Placeholder(String, String),
+    #[error("unclosed placeholder in layout {0:?}")]
+    Unclosed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Lang,
+    File,
+}
+
+/// where the file of a language lives below the base directory, a path template
+/// like `{base}/{file}.{lang}.json`. without one the files are found at
+/// `base/<lang>/file`, android's `base/values-<lang>/file`, apple's
+/// `base/<lang>.lproj/file` or, without a language directory, `base/file_<lang>.ext`.
+/// a new language is laid out like the ones that are there
+/// string catalogs (`.xcstrings`) are shared by all languages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    template: Option<Vec<Part>>,
+}
+impl FromStr for Layout {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = vec![];
+        let mut rest = s;
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_owned()));
+            }
+            let close = rest[open..]
+                .find('}')
+                .ok_or_else(|| Error::Unclosed(s.to_owned()))?;
+            let after = &rest[open + close + 1..];
+            rest = match &rest[open + 1..open + close] {
+                // paths are relative to the base
+                "base" if parts.is_empty() => after.trim_start_matches('/'),
+                "base" => return Err(Error::BaseNotFirst(s.to_owned())),
+                "lang" => {
+                    parts.push(Part::Lang);
+                    after
+                }
+                "file" => {
+                    parts.push(Part::File);
+                    after
+                }
+                name => return Err(Error::Placeholder(s.to_owned(), name.to_owned())),
+            };
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_owned()));
+        }
+        if !parts.contains(&Part::Lang) {
+            return Err(Error::NoLang(s.to_owned()));
+        }
+        if parts.iter().filter(|p| **p == Part::File).count() > 1 {
+            return Err(Error::ManyFiles(s.to_owned()));
+        }
+        Ok(Layout {
+            template: Some(parts),
+        })
+    }
+}
+impl Layout {
+    /// the path of `file` for `lang`, none when the layout needs a file and none is given
+    pub fn path(&self, base: &Path, lang: &str, file: Option<&Path>) -> Option<PathBuf> {
+        match &self.template {
+            None => {
+                let file = file?;
+                if is_catalog(file) {
+                    return Some(base.join(file));
+                }
+                let lang_dir =
+                    lang_dir(base, lang, Some(file)).or_else(|| new_lang_dir(base, lang, file));
+                Some(match lang_dir {
+                    Some(lang_dir) => lang_dir.join(file),
+                    None => base.join(lang_file_name(file, lang)),
+                })
+            }
+            Some(parts) => {
+                let mut path = String::new();
+                for part in parts {
+                    match part {
+                        Part::Literal(literal) => path.push_str(literal),
+                        Part::Lang => path.push_str(lang),
+                        Part::File => path.push_str(&file?.to_string_lossy()),
+                    }
+                }
+                Some(base.join(path))
+            }
+        }
+    }
+    /// the files of `lang` with their path relative to the language, which is
+    /// empty for layouts without a `{file}`
+    pub fn files(&self, base: &Path, lang: &str) -> Vec<(PathBuf, PathBuf)> {
+        match &self.template {
+            None => detect_files(base, lang),
+            Some(parts) => file_handling::list_files(base)
+                .into_iter()
+                .filter_map(|path| {
+                    let relative = path.strip_prefix(base).expect("listed below");
+                    let relative = relative.to_string_lossy().replace('\\', "/");
+                    let file = match_parts(parts, &relative, lang)?;
+                    Some((PathBuf::from(file), path))
+                })
+                .collect(),
+        }
+    }
+}
+/// the text `{file}` stands for when `path` fits the template
+fn match_parts(parts: &[Part], path: &str, lang: &str) -> Option<String> {
+    match parts.split_first() {
+        None => path.is_empty().then(String::new),
+        Some((Part::Literal(literal), rest)) => {
+            match_parts(rest, path.strip_prefix(literal)?, lang)
+        }
+        Some((Part::Lang, rest)) => match_parts(rest, path.strip_prefix(lang)?, lang),
+        Some((Part::File, rest)) => path
+            .char_indices()
+            .skip(1)
+            .map(|(i, _)| i)
+            .chain([path.len()])
+            .find(|&i| match_parts(rest, &path[i..], lang).is_some())
+            .map(|i| path[..i].to_owned()),
+    }
+}
+
+/// `messages.properties` -> `messages_nl.properties`
+fn lang_file_name(file: &Path, lang: &str) -> PathBuf {
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match file.extension() {
+        Some(ext) => format!("{stem}_{lang}.{}", ext.to_string_lossy()),
+        None => format!("{stem}_{lang}"),
+    };
+    file.with_file_name(name)
+}
+/// `messages_nl.properties` for `nl`
+fn has_lang_suffix(path: &Path, lang: &str) -> bool {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    stem.strip_suffix(lang).is_some_and(|s| s.ends_with('_'))
+}
+/// `messages_nl.properties` -> `messages.properties`
+fn strip_lang_suffix(file: &Path, lang: &str) -> PathBuf {
+    let name = file.file_name().unwrap_or_default().to_string_lossy();
+    file.with_file_name(name.replacen(&format!("_{lang}"), "", 1))
+}
+/// the existing `base/<lang>`, android's `base/values-<lang>` and apple's
+/// `base/<lang>.lproj` dirs
+fn lang_dirs(base: &Path, lang: &str) -> Vec<PathBuf> {
+    [lang.to_owned(), android_dir(lang), format!("{lang}.lproj")]
+        .into_iter()
+        .map(|dir| base.join(dir))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+/// android writes regions as `values-pt-rBR`
+fn android_dir(lang: &str) -> String {
+    match lang.split_once(['-', '_']) {
+        Some((lang, region)) => format!("values-{lang}-r{region}"),
+        None => format!("values-{lang}"),
+    }
+}
+/// `nl`, `pt-BR`, `zh_Hant`
+fn is_lang(name: &str) -> bool {
+    let mut parts = name.split(['-', '_']);
+    parts
+        .next()
+        .is_some_and(|l| (2..=3).contains(&l.len()) && l.chars().all(|c| c.is_ascii_lowercase()))
+        && parts.all(|p| (2..=4).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+/// the dir a new language gets when another language keeps `file` in a dir of
+/// its own, named the same way
+fn new_lang_dir(base: &Path, lang: &str, file: &Path) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(base).ok()?;
+    entries.flatten().find_map(|entry| {
+        let name = entry.file_name();
+        let name = name.to_str()?;
+        if !entry.path().join(file).is_file() {
+            return None;
+        }
+        let dir = if let Some(other) = name.strip_suffix(".lproj") {
+            is_lang(other).then(|| format!("{lang}.lproj"))
+        } else if name == "values" || name.starts_with("values-") {
+            Some(android_dir(lang))
+        } else {
+            is_lang(name).then(|| lang.to_owned())
+        };
+        dir.map(|dir| base.join(dir))
+    })
+}
+/// the dir of `lang`, preferring the one that holds `file`
+fn lang_dir(base: &Path, lang: &str, file: Option<&Path>) -> Option<PathBuf> {
+    let dirs = lang_dirs(base, lang);
+    let holding = dirs
+        .iter()
+        .find(|dir| file.is_some_and(|f| dir.join(f).exists()));
+    holding.or(dirs.first()).cloned()
+}
+/// apple string catalogs hold every language in one file
+fn is_catalog(file: &Path) -> bool {
+    file.extension().is_some_and(|e| e == "xcstrings")
+}
+fn detect_files(base: &Path, lang: &str) -> Vec<(PathBuf, PathBuf)> {
+    let catalogs = file_handling::list_files(base)
+        .into_iter()
+        .filter(|path| is_catalog(path))
+        .map(|path| (path.strip_prefix(base).expect("listed below").into(), path));
+    let dirs = lang_dirs(base, lang);
+    if !dirs.is_empty() {
+        return dirs
+            .iter()
+            .flat_map(|lang_dir| {
+                file_handling::list_files(lang_dir)
+                    .into_iter()
+                    .map(move |path| {
+                        let file = path.strip_prefix(lang_dir).expect("listed below").into();
+                        (file, path)
+                    })
+            })
+            .chain(catalogs)
+            .collect();
+    }
+    file_handling::list_files(base)
+        .into_iter()
+        .filter(|path| has_lang_suffix(path, lang))
+        .map(|path| {
+            let file = path.strip_prefix(base).expect("listed below");
+            (strip_lang_suffix(file, lang), path)
+        })
+        .chain(catalogs)
+        .collect()
+}
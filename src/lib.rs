@@ -1,8 +1,18 @@
 use std::path::PathBuf;
 
+pub mod audit;
+pub mod cassette;
+pub mod config_file;
+pub mod doctor;
 pub mod file_handling;
+pub mod format;
 pub mod lang;
+pub mod layout;
+pub mod secret;
+pub mod serializer;
+pub mod time;
 pub mod translate;
+pub mod usage;
 
 #[derive(Debug)]
 pub enum Input {
@@ -1,25 +1,46 @@
 use crate::config_parse::ToConfig;
+use anyhow::Context;
+use beetree::audit;
+use beetree::config_file::{self, ConfigFile};
+use beetree::doctor;
+use beetree::format::xliff;
+use beetree::serializer::ValueSerializer;
 use beetree::translate;
 use beetree::{lang, Input};
 use clap::error::ErrorKind;
-use clap::{arg, command, value_parser, Arg, ArgAction, Command};
-use std::env;
+use clap::parser::ValueSource;
+use clap::{arg, command, value_parser, Arg, ArgAction, ArgMatches, Command};
+use std::ffi::OsStr;
 use std::io::IsTerminal;
 use std::path::PathBuf;
 mod config_parse;
 
 fn cli() -> Command {
     command!()
-        .about("general utily cli for working on the beetree webapplication\nreads .env files")
+        .about("general utily cli for working on the beetree webapplication\nreads .env files and the closest beetree.toml\nsettings come from the cli, then the env, then beetree.toml, then the defaults")
         .arg_required_else_help(true)
         .subcommand_required(true)
         .subcommand(build_translate_command())
         .subcommand(build_lang_command())
+        .subcommand(build_config_command())
+        .subcommand(build_log_command())
+        .subcommand(build_doctor_command())
+        .subcommand(build_models_command())
         .arg(
             arg!(-v --verbose "Execute in verbose mode")
                 .global(true)
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            arg!(--profile <NAME> "settings from the [NAME] section of ~/.config/beetree/profiles.toml\nthey give way to the cli only")
+                .env("B3_PROFILE")
+                .global(true),
+        )
+        .arg(
+            arg!(env_file: --"env-file" <FILE> "env file to read instead of .env")
+                .global(true)
+                .value_parser(value_parser!(PathBuf)),
+        )
 }
 fn build_translate_command() -> Command {
     Command::new("translate")
@@ -37,33 +58,161 @@ fn build_translate_command() -> Command {
             arg!(input_file: -i --input <FILE> "path to input file")
                 .value_parser(value_parser!(PathBuf)),
         )
+        .args(llm_args())
         .arg(
-            arg!(--host <ADDR> "the address of the server running the llm")
-                .env("B3_HOST")
-                .required(true),
-        )
-        .arg(
-            arg!(api_key: --"api-key" <KEY> "api key for llm server")
-                .env("B3_KEY")
-                .default_value("dummy_key"),
+            arg!(--languages <LANGS> "list of the languages to translate to")
+                .env("B3_LANGUAGES")
+                .default_value("nl,fr,en"),
         )
         .arg(
-            arg!(--model <MODEL> "chosen model")
-                .env("B3_MODEL")
-                .required(true),
+            arg!(--stream "print every language as soon as it is translated")
+                .env("B3_STREAM")
+                .action(ArgAction::SetTrue),
         )
         .arg(
-            arg!(--languages <LANGS> "list of the languages to translate to")
-                .env("B3_LANGUAGES")
-                .default_value("nl,fr,en"),
+            arg!(--fluent "keep the placeables and select expressions of a fluent pattern\non for .ftl input files")
+                .action(ArgAction::SetTrue),
         )
 }
+fn llm_args() -> [Arg; 30] {
+    [
+        arg!(--host <ADDR> "the address of the server running the llm")
+            .env("B3_HOST")
+            .required(true),
+        arg!(api_key: --"api-key" <KEY> "api key for llm server\nthe key command, file or keyring take over unless given on the cli")
+            .env("B3_KEY")
+            .default_value("dummy_key"),
+        arg!(api_key_cmd: --"api-key-cmd" <CMD> "command printing the api key, e.g. 'pass show llm'")
+            .env("B3_KEY_CMD"),
+        arg!(api_key_file: --"api-key-file" <FILE> "file holding the api key")
+            .env("B3_KEY_FILE")
+            .value_parser(value_parser!(PathBuf)),
+        arg!(api_key_keyring: --"api-key-keyring" <ACCOUNT> "account of the api key in the secret service\nstored with: secret-tool store --label=beetree service beetree account ACCOUNT")
+            .env("B3_KEY_KEYRING"),
+        arg!(--model <MODEL> "chosen model")
+            .env("B3_MODEL")
+            .required(true),
+        arg!(--endpoint <URL> "where the chat completions are requested, with {host} and {model}\ne.g. {host}/openai/deployments/{model}/chat/completions?api-version=2024-10-21 for azure")
+            .env("B3_ENDPOINT")
+            .default_value(translate::DEFAULT_ENDPOINT),
+        arg!(--auth <MODE> "how the api key is sent: as bearer token, in the --auth-header or not at all")
+            .env("B3_AUTH")
+            .value_parser(["bearer", "header", "none"])
+            .default_value("bearer"),
+        arg!(auth_header: --"auth-header" <NAME> "the header holding the api key with --auth header")
+            .env("B3_AUTH_HEADER")
+            .default_value("api-key"),
+        arg!(headers: --header <HEADER> "extra header sent with every request, as 'Name: value'\nrepeat it for more, one per line in the env")
+            .env("B3_HEADERS")
+            .action(ArgAction::Append),
+        arg!(connect_timeout: --"connect-timeout" <SECONDS> "how long connecting to the host may take")
+            .env("B3_CONNECT_TIMEOUT")
+            .value_parser(value_parser!(u64)),
+        arg!(--timeout <SECONDS> "how long a request may take in total, connecting until the whole answer is read\nstreamed answers too have to be done by then")
+            .env("B3_TIMEOUT")
+            .value_parser(value_parser!(u64))
+            .default_value("30"),
+        arg!(--proxy <URL> "proxy for http and https, instead of HTTPS_PROXY")
+            .env("B3_PROXY"),
+        arg!(ca_certs: --"ca-cert" <FILE> "pem file of certificates to trust besides the system's, can be repeated")
+            .env("B3_CA_CERT")
+            .value_parser(value_parser!(PathBuf))
+            .action(ArgAction::Append),
+        arg!(--insecure "don't verify tls certificates, only for local development")
+            .env("B3_INSECURE")
+            .action(ArgAction::SetTrue),
+        arg!(--retries <N> "send a request again up to N times while the host is busy or out of reach\nwaiting a second, then twice as long each time")
+            .env("B3_RETRIES")
+            .value_parser(value_parser!(u32))
+            .default_value("2"),
+        arg!(check_model: --"check-model" "check that the host lists the model before translating")
+            .env("B3_CHECK_MODEL")
+            .action(ArgAction::SetTrue),
+        arg!(--temperature <TEMP> "randomness of the wording, the server's default when not given\nrecommended: 0 with --seed 1, which keeps ui copy stable between runs\nleave it out for models that refuse it, like reasoning models")
+            .env("B3_TEMPERATURE")
+            .value_parser(value_parser!(f64)),
+        arg!(top_p: --"top-p" <P> "only pick from the most likely words that add up to P")
+            .env("B3_TOP_P")
+            .value_parser(value_parser!(f64)),
+        arg!(--seed <SEED> "seed of the sampling, for the providers that support it\nfixed so that runs at the same temperature pick the same words")
+            .env("B3_SEED")
+            .value_parser(value_parser!(u64)),
+        arg!(max_tokens: --"max-tokens" <TOKENS> "the most tokens an answer may take")
+            .env("B3_MAX_TOKENS")
+            .value_parser(value_parser!(u64)),
+        arg!(prompt_file: --"prompt-file" <FILE> "extra instructions added to the translation prompt")
+            .env("B3_PROMPT_FILE")
+            .value_parser(value_parser!(PathBuf)),
+        arg!(--glossary <FILE> "terms and how to translate them, added to the translation prompt")
+            .env("B3_GLOSSARY")
+            .value_parser(value_parser!(PathBuf)),
+        arg!(--stats "report the tokens and cost of the requests, and of the month so far with a --ledger")
+            .env("B3_STATS")
+            .action(ArgAction::SetTrue),
+        arg!(--prices <FILE> "dollars per million tokens of each model, a toml file like\n[gpt-4o-mini]\nprompt = 0.15\ncompletion = 0.6")
+            .env("B3_PRICES")
+            .value_parser(value_parser!(PathBuf)),
+        arg!(--ledger <FILE> "keep the usage of every request in this file for the monthly totals\ne.g. ~/.local/share/beetree/ledger.tsv, nothing is kept without it")
+            .env("B3_LEDGER")
+            .value_parser(value_parser!(PathBuf)),
+        arg!(no_ledger: --"no-ledger" "keep the usage out of the ledger, even when one is set")
+            .env("B3_NO_LEDGER")
+            .action(ArgAction::SetTrue),
+        arg!(max_tokens_total: --"max-tokens-total" <TOKENS> "stop before the requests of the command would use more tokens")
+            .env("B3_MAX_TOKENS_TOTAL")
+            .value_parser(value_parser!(u64)),
+        arg!(max_cost: --"max-cost" <DOLLARS> "stop before the requests of the command would cost more\nneeds a price for the model, see --prices")
+            .env("B3_MAX_COST")
+            .value_parser(value_parser!(f64)),
+        audit_log_arg(),
+    ]
+}
+fn audit_log_arg() -> Arg {
+    arg!(audit_log: --"audit-log" <FILE> "keep every request to the llm host and its answer in this jsonl file\nthe api key, header values and url password are redacted")
+        .env("B3_AUDIT_LOG")
+        .value_parser(value_parser!(PathBuf))
+}
+/// where the language files are
+fn location_args() -> [Arg; 2] {
+    [
+        arg!(base_path: --"base" <DIR> "path to branching language directory")
+            .env("B3_BASE_PATH")
+            .default_value(".")
+            .value_parser(value_parser!(PathBuf)),
+        arg!(--layout <TEMPLATE> "where the file of a language lives, e.g. {base}/{file}.{lang}.json\nwith {base}, {lang} and {file} placeholders\nfinds <lang>/, values-<lang>/, <lang>.lproj/ or file_<lang> when not given\n.xcstrings catalogs hold every language")
+            .env("B3_LAYOUT"),
+    ]
+}
+fn build_doctor_command() -> Command {
+    Command::new("doctor")
+        .about("checks the settings, that every language has the same files\nand that the llm host answers, lists the model and translates")
+        .args(location_args())
+        .arg(arg!(--languages <LANGS> "list of the languages to check")
+            .env("B3_LANGUAGES")
+            .default_value("nl,fr,en"))
+        // reported instead of refused when missing
+        .args(llm_args().map(|a| a.required(false)))
+}
+fn build_models_command() -> Command {
+    Command::new("models")
+        .about("lists the models of the llm host (openai /models or ollama /api/tags)\nthe selected model is marked with *")
+        // the model is only marked when given
+        .args(llm_args().map(|a| {
+            if a.get_id() == "model" {
+                a.required(false)
+            } else {
+                a
+            }
+        }))
+}
 fn build_lang_command() -> Command {
-    let src_tag = arg!([source_tag] "tag name of the created language binding");
-    let dest_tag = arg!([destination_tag] "tag to be searched for");
+    // fluent terms like `-brand-name` start with a hyphen
+    let src_tag =
+        arg!([source_tag] "tag name of the created language binding").allow_hyphen_values(true);
+    let dest_tag = arg!([destination_tag] "tag to be searched for").allow_hyphen_values(true);
     let input_file = arg!(input_file: -i --input <FILE> "path to input file")
         .value_parser(value_parser!(PathBuf));
-    let _output_file = arg!(output_file: -o --output <FILE> "path to output file")
+    let output_file = arg!(output_file: -o --output <FILE> "path to output file")
         .value_parser(value_parser!(PathBuf));
     let search_file =
         arg!(search_file: -f --file <FILE> "path to file (per language) to specify search.")
@@ -75,12 +224,12 @@ fn build_lang_command() -> Command {
         .about("transfers language translations to their respective files")
         .arg_required_else_help(true)
         .subcommand_required(true)
+        .args(location_args().map(|a| a.global(true)))
         .arg(
-            arg!(base_path: --"base" <DIR> "path to branching language directory")
-                .env("B3_BASE_PATH")
-                .default_value(".")
+            arg!(--serializer <FORMAT> "how values are escaped\ninferred from the file extension when not given")
+                .env("B3_SERIALIZER")
                 .global(true)
-                .value_parser(value_parser!(PathBuf))
+                .value_parser(ValueSerializer::NAMES)
         )
         .subcommand(Command::new("append")
             .about("append the translations to the chosen file")
@@ -105,6 +254,13 @@ fn build_lang_command() -> Command {
             .arg(&input_file)
             .arg(&search_file)
         )
+        .subcommand(Command::new("replace")
+            .about("replaces the value of the destination tag with the translations")
+            .arg(dest_tag.clone().required(true))
+            .arg(&text)
+            .arg(&input_file)
+            .arg(&search_file)
+        )
         .subcommand(Command::new("remove")
             .about("deletes the variable of the file it appears in\nonly one line variables supported\nonly deletes first appearance")
             .arg(&search_file)
@@ -113,6 +269,207 @@ fn build_lang_command() -> Command {
                 .default_value("nl,fr,en"))
             .arg(dest_tag.clone().required(true))
         )
+        .subcommand(Command::new("fmt")
+            .about("sorts keys and normalizes quoting of the language files\nformats every file per language when no file given")
+            .arg(&search_file)
+            .arg(arg!(--languages <LANGS> "list of the languages to format")
+                .env("B3_LANGUAGES")
+                .default_value("nl,fr,en"))
+            .arg(arg!(--check "only report files that are not formatted").action(ArgAction::SetTrue))
+            .arg(arg!(--sections "sort keys within comment delimited sections").action(ArgAction::SetTrue))
+        )
+        .subcommand(Command::new("po-fill")
+            .about("translates the empty msgstr entries of the gettext files\n<lang>/LC_MESSAGES/<name>.po files are created from the template when missing")
+            .arg(arg!(<template> "path to the .pot template").value_parser(value_parser!(PathBuf)))
+            .arg(arg!(--languages <LANGS> "list of the languages to fill")
+                .env("B3_LANGUAGES")
+                .default_value("nl,fr,en"))
+            .args(llm_args())
+        )
+        .subcommand(Command::new("export-xliff")
+            .about("writes the keys of the source language and their translations as xliff\nexports every file of the source language when no file given")
+            .arg(arg!(--source <LANG> "language translated from").required(true))
+            .arg(arg!(--target <LANG> "language translated to").required(true))
+            .arg(&search_file)
+            .arg(&output_file)
+            .arg(arg!(xliff_version: --"xliff-version" <VERSION> "xliff version to write")
+                .value_parser(xliff::Version::NAMES)
+                .default_value("1.2"))
+        )
+        .subcommand(Command::new("import-xliff")
+            .about("writes the translated units of an xliff file back to the language files\nunits whose source changed since the export are reported and skipped")
+            .arg(arg!(<xliff> "path to the xliff file").value_parser(value_parser!(PathBuf)))
+        )
+        .subcommand(Command::new("export-csv")
+            .about("writes a row per key with its value in every language as csv\nexports every file when no file given\ncells starting with = + - or @ get a ' in front so spreadsheets don't run them as formulas")
+            .arg(arg!(--languages <LANGS> "list of the languages to export")
+                .env("B3_LANGUAGES")
+                .default_value("nl,fr,en"))
+            .arg(&search_file)
+            .arg(&output_file)
+        )
+        .subcommand(Command::new("import-csv")
+            .about("writes the edited cells of an exported csv back to the language files\nshows the changes first, empty cells are left alone\nrows whose files changed since the export are reported and skipped")
+            .arg(arg!(<csv> "path to the csv file").value_parser(value_parser!(PathBuf)))
+            .arg(arg!(-y --yes "apply without asking").action(ArgAction::SetTrue))
+        )
+}
+fn build_log_command() -> Command {
+    Command::new("log")
+        .about("inspects the audit log of the requests to the llm host")
+        .arg_required_else_help(true)
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("show")
+                .about("prints the records of the audit log, filtered by date or text")
+                .arg(audit_log_arg().required(true))
+                .arg(arg!(--since <DATE> "only records from this day on, e.g. 2025-03-14 or 2025-03"))
+                .arg(arg!(--until <DATE> "only records up to and including this day or month"))
+                .arg(arg!(--key <TEXT> "only requests whose translated text contains it")),
+        )
+}
+fn build_config_command() -> Command {
+    Command::new("config")
+        .about("inspects the settings of beetree.toml")
+        .arg_required_else_help(true)
+        .subcommand_required(true)
+        .subcommand(Command::new("show").about(
+            "prints the effective settings and where they come from\nthe cli, then the env, then beetree.toml, then the defaults",
+        ))
+}
+/// makes the values of the config file (or profile) the defaults of the
+/// arguments they belong to. a profile also takes over from the env
+fn with_defaults(mut cmd: Command, settings: &ConfigFile) -> Command {
+    for (id, _) in &settings.values {
+        if cmd.get_arguments().any(|a| a.get_id() == id) {
+            let values = settings.get(id);
+            // clap doesn't count defaults as given
+            cmd = cmd.mut_arg(id, |a| {
+                let a = a.default_values(values).required(false);
+                match settings.profile {
+                    Some(_) => a.env(None),
+                    None => a,
+                }
+            });
+        }
+    }
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|s| s.get_name().to_owned())
+        .collect();
+    for name in names {
+        cmd = cmd.mut_subcommand(name, |s| with_defaults(s, settings));
+    }
+    cmd
+}
+/// the first argument named `id` in `cmd` or its subcommands
+fn find_arg<'a>(cmd: &'a Command, id: &str) -> Option<&'a Arg> {
+    cmd.get_arguments()
+        .find(|a| a.get_id() == id)
+        .or_else(|| cmd.get_subcommands().find_map(|s| find_arg(s, id)))
+}
+/// `--name value` or `--name=value`, for what has to be known before the cli is built
+fn early_arg(name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            return None;
+        }
+        if arg == flag {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(&flag).and_then(|a| a.strip_prefix('=')) {
+            return Some(value.to_owned());
+        }
+    }
+    None
+}
+/// the innermost subcommand's matches
+fn leaf_matches(matches: &ArgMatches) -> &ArgMatches {
+    match matches.subcommand() {
+        Some((_, matches)) => leaf_matches(matches),
+        None => matches,
+    }
+}
+/// every setting with its value and where that comes from, in order of precedence:
+/// the cli, a profile, the env, beetree.toml and the defaults
+fn setting_sources(
+    cmd: &Command,
+    profile: Option<&ConfigFile>,
+    config_file: Option<&ConfigFile>,
+    matches: Option<&ArgMatches>,
+) -> Vec<(&'static str, Option<(String, String)>)> {
+    let from = |settings: Option<&ConfigFile>, id: &str| {
+        let settings = settings?;
+        let values = settings.get(id);
+        (!values.is_empty()).then(|| (values.join(","), settings.source()))
+    };
+    config_file::KEYS
+        .iter()
+        .map(|key| {
+            let arg = find_arg(cmd, key.arg).expect("keys belong to arguments");
+            let cli = matches
+                .filter(|m| m.ids().any(|id| id == key.arg))
+                .filter(|m| m.value_source(key.arg) == Some(ValueSource::CommandLine))
+                .and_then(|m| m.get_raw(key.arg))
+                .map(|values| (join_raw(values), "cli".to_owned()));
+            let env = || {
+                let env = arg.get_env()?;
+                let value = std::env::var_os(env)?.to_string_lossy().into_owned();
+                Some((value, format!("env {}", env.to_string_lossy())))
+            };
+            let default = || {
+                let values = arg.get_default_values().iter().map(AsRef::as_ref);
+                (values.len() > 0).then(|| (join_raw(values), "default".to_owned()))
+            };
+            let found = cli
+                .or_else(|| from(profile, key.arg))
+                .or_else(env)
+                .or_else(|| from(config_file, key.arg))
+                .or_else(default)
+                .map(|(value, source)| {
+                    let value = if key.secret {
+                        "<redacted>".to_owned()
+                    } else if key.map {
+                        // header values tend to be credentials too
+                        redact_values(&value)
+                    } else {
+                        value
+                    };
+                    (value, source)
+                });
+            (key.name, found)
+        })
+        .collect()
+}
+/// the values of a repeated argument, comma separated
+fn join_raw<'a>(values: impl IntoIterator<Item = &'a OsStr>) -> String {
+    let values: Vec<_> = values.into_iter().map(OsStr::to_string_lossy).collect();
+    values.join(",")
+}
+/// `Name: value` lines with the values hidden
+fn redact_values(lines: &str) -> String {
+    lines
+        .lines()
+        .map(|line| match line.split_once(':') {
+            Some((name, _)) => format!("{name}: <redacted>"),
+            None => "<redacted>".to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+fn show_config(settings: &[(&str, Option<(String, String)>)], config_file: Option<&ConfigFile>) {
+    match config_file {
+        Some(config_file) => println!("# {}", config_file.path.display()),
+        None => println!("# no {} found", config_file::FILE_NAME),
+    }
+    for (name, found) in settings {
+        match found {
+            Some((value, source)) => println!("{name} = {value:?} # {source}"),
+            None => println!("# {name} is not set"),
+        }
+    }
 }
 fn get_terminal_pipe_input(cmd: &mut Command, arg_id: &str, text: String) -> String {
     if text == "-" {
@@ -131,9 +488,45 @@ fn get_terminal_pipe_input(cmd: &mut Command, arg_id: &str, text: String) -> Str
 }
 
 fn main() -> anyhow::Result<()> {
-    let _ = dotenvy::dotenv();
+    match early_arg("env-file") {
+        Some(path) => {
+            dotenvy::from_path(&path).with_context(|| format!("error: reading env file {path}"))?
+        }
+        None => {
+            let _ = dotenvy::dotenv();
+        }
+    };
+    let profile = early_arg("profile")
+        .or_else(|| std::env::var("B3_PROFILE").ok())
+        .map(|name| ConfigFile::profile(&name))
+        .transpose()?;
+    let config_file = ConfigFile::discover()?;
     let mut cmd = cli();
+    // the profile goes last so it wins
+    for settings in config_file.iter().chain(&profile) {
+        cmd = with_defaults(cmd, settings);
+    }
     let matches = cmd.get_matches_mut();
+    if matches.get_flag("verbose") {
+        if let Some(profile) = &profile {
+            eprintln!(
+                "profile: {} ({})",
+                profile.profile.as_deref().unwrap_or_default(),
+                profile.path.display()
+            );
+        }
+        let sources = setting_sources(
+            &cmd,
+            profile.as_ref(),
+            config_file.as_ref(),
+            Some(leaf_matches(&matches)),
+        );
+        for (name, found) in sources {
+            if let Some((value, source)) = found {
+                eprintln!("{name} = {value:?} from {source}");
+            }
+        }
+    }
     match matches.subcommand() {
         Some(("translate", args)) => {
             let cmd = cmd.find_subcommand_mut("translate").expect("curr scmd");
@@ -173,9 +566,66 @@ fn main() -> anyhow::Result<()> {
                     }
                     lang::insert(config)?;
                 }
+                Some(("replace", args)) => {
+                    let cmd = cmd.find_subcommand_mut("replace").expect("curr scmd");
+                    let mut config: lang::ReplaceConfig = args.to_config()?;
+                    if let Input::Text(text) = config.input {
+                        let text = get_terminal_pipe_input(cmd, "text", text);
+                        config.input = Input::Text(text);
+                    }
+                    lang::replace(config)?;
+                }
+                Some(("po-fill", args)) => {
+                    let config: lang::PoFillConfig = args.to_config()?;
+                    lang::po_fill(config)?;
+                }
+                Some(("export-xliff", args)) => {
+                    let config: lang::ExportXliffConfig = args.to_config()?;
+                    lang::export_xliff(config)?;
+                }
+                Some(("import-xliff", args)) => {
+                    let config: lang::ImportXliffConfig = args.to_config()?;
+                    lang::import_xliff(config)?;
+                }
+                Some(("export-csv", args)) => {
+                    let config: lang::ExportCsvConfig = args.to_config()?;
+                    lang::export_csv(config)?;
+                }
+                Some(("import-csv", args)) => {
+                    let config: lang::ImportCsvConfig = args.to_config()?;
+                    lang::import_csv(config)?;
+                }
+                Some(("fmt", args)) => {
+                    let config: lang::FmtConfig = args.to_config()?;
+                    lang::fmt(config)?;
+                }
                 _ => todo!(),
             }
         }
+        Some(("models", args)) => {
+            let config: translate::ModelsConfig = args.to_config()?;
+            translate::list_models(config)?;
+        }
+        Some(("doctor", args)) => {
+            let mut config: doctor::Config = args.to_config()?;
+            config.config_file = config_file.map(|c| c.path);
+            config.profile = profile.and_then(|p| p.profile);
+            doctor::run(config)?;
+        }
+        Some(("log", args)) => match args.subcommand() {
+            Some(("show", args)) => {
+                let config: audit::ShowConfig = args.to_config()?;
+                audit::show(config)?;
+            }
+            _ => unreachable!("clap requires a log subcommand"),
+        },
+        Some(("config", args)) => match args.subcommand() {
+            Some(("show", _)) => {
+                let sources = setting_sources(&cmd, profile.as_ref(), config_file.as_ref(), None);
+                show_config(&sources, config_file.as_ref());
+            }
+            _ => unreachable!("clap requires a config subcommand"),
+        },
         Some((subcommand, _)) => panic!("clap handles invaled subommand: {subcommand:?}"),
         None => {}
     }
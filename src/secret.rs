@@ -0,0 +1,90 @@
+use crate::file_handling;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    FileError(#[from] file_handling::Error),
+    #[error("error: running {0}")]
+    Run(String, #[source] std::io::Error),
+    #[error("{0} failed: {1}")]
+    Failed(String, String),
+    #[error("{0} gave an empty api key")]
+    Empty(String),
+}
+
+/// a value kept out of debug output
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// where to get the api key from instead of plain text
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// a shell command printing the key, like `pass show llm`
+    Command(String),
+    File(PathBuf),
+    /// the account of the key in the secret service, as stored by
+    /// `secret-tool store --label=beetree service beetree account <account>`
+    Keyring(String),
+}
+impl KeySource {
+    pub fn resolve(&self) -> Result<Secret, Error> {
+        let (name, key) = match self {
+            KeySource::Command(command) => (command.clone(), run(shell(command), command)?),
+            KeySource::File(path) => (
+                path.display().to_string(),
+                file_handling::read_from_file(path)?,
+            ),
+            KeySource::Keyring(account) => {
+                let mut command = Command::new("secret-tool");
+                command.args(["lookup", "service", "beetree", "account", account]);
+                let name = format!("secret-tool lookup for {account}");
+                let key = run(command, &name)?;
+                (name, key)
+            }
+        };
+        // commands and files end with a new line
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(Error::Empty(name));
+        }
+        Ok(Secret::new(key))
+    }
+}
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+fn run(mut command: Command, name: &str) -> Result<String, Error> {
+    let output = command
+        .output()
+        .map_err(|e| Error::Run(name.to_owned(), e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        return Err(Error::Failed(name.to_owned(), stderr));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,195 @@
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("unknown serializer: {0} (expected dotenv, json, js or properties)")]
+    Unknown(String),
+}
+
+/// how a translated value is escaped and written as `key` + value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueSerializer {
+    /// `KEY="value"`
+    Dotenv,
+    /// `"key": "value",`
+    Json,
+    /// `key: "value",` for js and ts objects
+    Js,
+    /// `key=value` for java properties
+    Properties,
+}
+impl FromStr for ValueSerializer {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dotenv" | "env" => Ok(Self::Dotenv),
+            "json" => Ok(Self::Json),
+            "js" | "ts" => Ok(Self::Js),
+            "properties" => Ok(Self::Properties),
+            _ => Err(Error::Unknown(s.to_owned())),
+        }
+    }
+}
+impl ValueSerializer {
+    pub const NAMES: [&'static str; 4] = ["dotenv", "json", "js", "properties"];
+
+    /// infers the serializer from the file extension, dotenv when unknown
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let ext = path.as_ref().extension().and_then(|e| e.to_str());
+        match ext {
+            Some("json") => Self::Json,
+            Some("js" | "mjs" | "cjs" | "jsx" | "ts" | "mts" | "cts" | "tsx") => Self::Js,
+            Some("properties") => Self::Properties,
+            _ => Self::Dotenv,
+        }
+    }
+    /// the chosen serializer or the one inferred from `path`
+    pub fn resolve(chosen: Option<Self>, path: impl AsRef<Path>) -> Self {
+        chosen.unwrap_or_else(|| Self::from_path(path))
+    }
+    /// the value as it should appear in the file, including quotes
+    pub fn quote(&self, value: &str) -> String {
+        match self {
+            Self::Dotenv => quote_dotenv(value),
+            Self::Json => serde_json::to_string(value).expect("strings serialize"),
+            Self::Js => serde_json::to_string(value)
+                .expect("strings serialize")
+                .replace('\u{2028}', "\\u2028")
+                .replace('\u{2029}', "\\u2029"),
+            Self::Properties => escape_properties(value, false),
+        }
+    }
+    /// the value written as `raw` (quotes included, trailing `,` left out)
+    pub fn unquote(&self, raw: &str) -> String {
+        if *self == Self::Properties {
+            // an escaped trailing space is part of the value
+            return unescape_properties(raw.trim_start());
+        }
+        let raw = raw.trim();
+        let quoted = |q: char| raw.len() >= 2 && raw.starts_with(q) && raw.ends_with(q);
+        match self {
+            Self::Dotenv if quoted('"') => unescape_dotenv(&raw[1..raw.len() - 1]),
+            Self::Dotenv if quoted('\'') => raw[1..raw.len() - 1].to_owned(),
+            Self::Dotenv => raw.to_owned(),
+            _ if quoted('\'') => raw[1..raw.len() - 1].replace("\\'", "'"),
+            _ => serde_json::from_str(raw).unwrap_or_else(|_| raw.to_owned()),
+        }
+    }
+    /// a full line binding `key` to `value`
+    pub fn entry(&self, key: &str, value: &str) -> String {
+        let value = self.quote(value);
+        match self {
+            Self::Dotenv => format!("{key}={value}"),
+            Self::Json => format!("{}: {value},", Self::Json.quote(key)),
+            Self::Js if is_js_identifier(key) => format!("{key}: {value},"),
+            Self::Js => format!("{}: {value},", Self::Js.quote(key)),
+            Self::Properties => format!("{}={value}", escape_properties(key, true)),
+        }
+    }
+}
+
+fn quote_dotenv(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+fn unescape_dotenv(inner: &str) -> String {
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+/// resolves `\uXXXX`, `\t`, `\n`, `\r`, `\f` and `\x` -> `x`
+pub(crate) fn unescape_properties(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    let mut pending_high: Option<u16> = None;
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{0c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let Ok(unit) = u16::from_str_radix(&hex, 16) else {
+                    out.push_str("\\u");
+                    out.push_str(&hex);
+                    continue;
+                };
+                match pending_high.take() {
+                    Some(high) => out.extend(char::decode_utf16([high, unit]).flatten()),
+                    None if (0xd800..0xdc00).contains(&unit) => pending_high = Some(unit),
+                    None => out.extend(char::from_u32(unit.into())),
+                }
+            }
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
+/// escapes like `java.util.Properties::store`, non ascii becomes `\uXXXX`
+fn escape_properties(value: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{0c}' => out.push_str("\\f"),
+            ' ' if is_key || i == 0 => out.push_str("\\ "),
+            '=' | ':' | '#' | '!' if is_key || i == 0 => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c),
+            c => {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    write!(out, "\\u{unit:04X}").expect("writing to string");
+                }
+            }
+        }
+    }
+    out
+}
+fn is_js_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
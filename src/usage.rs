@@ -0,0 +1,322 @@
+use crate::{file_handling, time};
+use serde_json::Value;
+use std::cell::Cell;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    FileError(#[from] file_handling::Error),
+    #[error("error: reading {0}")]
+    Parse(PathBuf, #[source] toml::de::Error),
+    #[error("the price of {1} in {0} needs a prompt and a completion number")]
+    Price(PathBuf, String),
+    #[error("invalid line {1} in {0}")]
+    Ledger(PathBuf, usize),
+    #[error("about {estimate} more tokens would go over the budget of {max}, {used} used")]
+    Tokens { used: u64, estimate: u64, max: u64 },
+    #[error("about ${estimate:.4} more would go over the budget of ${max}, ${used:.4} spent")]
+    Cost { used: f64, estimate: f64, max: f64 },
+    #[error("no price for {0} to keep to the cost budget")]
+    NoPrice(String),
+}
+
+/// the tokens a request took
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+impl Usage {
+    /// the `usage` block of an answer
+    pub fn from_json(json: &Value) -> Option<Usage> {
+        let usage = &json["usage"];
+        Some(Usage {
+            prompt_tokens: usage["prompt_tokens"].as_u64()?,
+            completion_tokens: usage["completion_tokens"].as_u64()?,
+        })
+    }
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+    }
+}
+
+/// dollars per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Price {
+    pub prompt: f64,
+    pub completion: f64,
+}
+/// the prices per model, read from a file like
+/// ```toml
+/// [gpt-4o-mini]
+/// prompt = 0.15
+/// completion = 0.6
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Prices(Vec<(String, Price)>);
+impl Prices {
+    pub fn load(path: &Path) -> Result<Prices, Error> {
+        let text = file_handling::read_from_file(path)?;
+        let table: toml::Table =
+            toml::from_str(&text).map_err(|e| Error::Parse(path.to_owned(), e))?;
+        let price = |value: &toml::Value, key| match value.get(key)? {
+            toml::Value::Float(f) => Some(*f),
+            toml::Value::Integer(i) => Some(*i as f64),
+            _ => None,
+        };
+        table
+            .iter()
+            .map(|(model, value)| {
+                let price = price(value, "prompt")
+                    .zip(price(value, "completion"))
+                    .map(|(prompt, completion)| Price { prompt, completion })
+                    .ok_or_else(|| Error::Price(path.to_owned(), model.clone()))?;
+                Ok((model.clone(), price))
+            })
+            .collect::<Result<_, _>>()
+            .map(Prices)
+    }
+    /// none for models without a price
+    pub fn cost(&self, model: &str, usage: Usage) -> Option<f64> {
+        let (_, price) = self.0.iter().find(|(m, _)| m == model)?;
+        Some(
+            (usage.prompt_tokens as f64 * price.prompt
+                + usage.completion_tokens as f64 * price.completion)
+                / 1_000_000.0,
+        )
+    }
+}
+
+/// a rough count of the tokens of `text`, about four characters each
+pub fn estimate_tokens(text: &str) -> u64 {
+    text.chars().count().div_ceil(4) as u64
+}
+
+/// the most a command may use, over all its requests
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Budget {
+    pub max_tokens: Option<u64>,
+    /// in dollars, needs a price for the model
+    pub max_cost: Option<f64>,
+}
+
+/// the usage per month and model, a `month model prompt completion cost` line
+/// per request, tab separated. the cost is `-` for models without a price
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    pub path: PathBuf,
+}
+/// a line of the ledger
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub month: String,
+    pub model: String,
+    pub usage: Usage,
+    /// none when the model had no price
+    pub cost: Option<f64>,
+}
+impl Ledger {
+    pub fn entries(&self) -> Result<Vec<Entry>, Error> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let text = file_handling::read_from_file(&self.path)?;
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_entry(line).ok_or_else(|| Error::Ledger(self.path.clone(), i + 1))
+            })
+            .collect()
+    }
+    /// appends the line of `usage` in a single write, so commands running at
+    /// the same time don't lose each other's requests
+    pub fn add(
+        &self,
+        month: &str,
+        model: &str,
+        usage: Usage,
+        cost: Option<f64>,
+    ) -> Result<(), Error> {
+        let cost = cost.map_or("-".to_owned(), |cost| cost.to_string());
+        let line = format!(
+            "{month}\t{model}\t{}\t{}\t{cost}\n",
+            usage.prompt_tokens, usage.completion_tokens
+        );
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| file_handling::Error::Write(dir.to_owned(), e))?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| file_handling::Error::Write(self.path.clone(), e))?;
+        Ok(())
+    }
+}
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut fields = line.split('\t');
+    let entry = Entry {
+        month: fields.next()?.to_owned(),
+        model: fields.next()?.to_owned(),
+        usage: Usage {
+            prompt_tokens: fields.next()?.parse().ok()?,
+            completion_tokens: fields.next()?.parse().ok()?,
+        },
+        cost: match fields.next()? {
+            "-" => None,
+            cost => Some(cost.parse().ok()?),
+        },
+    };
+    fields.next().is_none().then_some(entry)
+}
+/// `2025-03`, in utc
+pub fn current_month() -> String {
+    time::utc(SystemTime::now())[..7].to_owned()
+}
+
+/// counts the tokens and cost of the requests of a command
+#[derive(Debug, Clone, Default)]
+pub struct Meter {
+    prices: Prices,
+    ledger: Option<Ledger>,
+    budget: Budget,
+    used: Cell<Usage>,
+    cost: Cell<f64>,
+    /// requests sent by the command
+    requests: Cell<usize>,
+}
+impl Meter {
+    pub fn new(prices: Prices, ledger: Option<Ledger>, budget: Budget) -> Self {
+        Meter {
+            prices,
+            ledger,
+            budget,
+            ..Meter::default()
+        }
+    }
+    /// fails when a request of about `estimate` would go over the budget
+    pub fn check(&self, model: &str, estimate: Usage) -> Result<(), Error> {
+        if let Some(max) = self.budget.max_tokens {
+            let used = self.used().total();
+            if used + estimate.total() > max {
+                return Err(Error::Tokens {
+                    used,
+                    estimate: estimate.total(),
+                    max,
+                });
+            }
+        }
+        if let Some(max) = self.budget.max_cost {
+            let estimate = self
+                .prices
+                .cost(model, estimate)
+                .ok_or_else(|| Error::NoPrice(model.to_owned()))?;
+            let used = self.cost();
+            if used + estimate > max {
+                return Err(Error::Cost {
+                    used,
+                    estimate,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+    /// counts a request, the ledger only gets a warning when it can't be written
+    pub fn record(&self, model: &str, usage: Usage, verbose: bool) {
+        let cost = self.prices.cost(model, usage);
+        self.used.set({
+            let mut used = self.used.get();
+            used += usage;
+            used
+        });
+        self.cost.set(self.cost.get() + cost.unwrap_or_default());
+        self.requests.set(self.requests.get() + 1);
+        if verbose {
+            eprintln!("usage: {}", describe(usage, cost));
+        }
+        if let Some(ledger) = &self.ledger {
+            let added = ledger.add(&current_month(), model, usage, cost);
+            if let Err(e) = added {
+                eprintln!("warning: not counted in the ledger: {e}");
+            }
+        }
+    }
+    pub fn used(&self) -> Usage {
+        self.used.get()
+    }
+    pub fn cost(&self) -> f64 {
+        self.cost.get()
+    }
+    /// what the command used and the month so far
+    pub fn print_stats(&self, model: &str) {
+        let cost = self
+            .prices
+            .cost(model, Usage::default())
+            .map(|_| self.cost());
+        eprintln!(
+            "{} request(s): {}",
+            self.requests.get(),
+            describe(self.used(), cost)
+        );
+        let Some(ledger) = &self.ledger else {
+            return;
+        };
+        let month = current_month();
+        match ledger.entries() {
+            Ok(entries) => {
+                let mut used = Usage::default();
+                let mut cost = 0.0;
+                let mut unpriced: Vec<&str> = vec![];
+                for entry in entries.iter().filter(|e| e.month == month) {
+                    used += entry.usage;
+                    match entry.cost {
+                        Some(entry_cost) => cost += entry_cost,
+                        None if !unpriced.contains(&entry.model.as_str()) => {
+                            unpriced.push(&entry.model)
+                        }
+                        None => {}
+                    }
+                }
+                let total = describe(used, Some(cost));
+                if unpriced.is_empty() {
+                    eprintln!("{month}: {total}");
+                } else {
+                    // the cost leaves out the requests of these models
+                    eprintln!(
+                        "{month}: {total}, incomplete: no price for {}",
+                        unpriced.join(", ")
+                    );
+                }
+            }
+            Err(e) => eprintln!("warning: {e}"),
+        }
+    }
+}
+/// `120 tokens (100 prompt, 20 completion), $0.0012`
+fn describe(usage: Usage, cost: Option<f64>) -> String {
+    let tokens = format!(
+        "{} tokens ({} prompt, {} completion)",
+        usage.total(),
+        usage.prompt_tokens,
+        usage.completion_tokens
+    );
+    match cost {
+        Some(cost) => format!("{tokens}, ${cost:.4}"),
+        None => format!("{tokens}, no price"),
+    }
+}
@@ -0,0 +1,88 @@
+use beetree::config_file::{ConfigFile, Error};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `text` as the beetree.toml of a fresh directory below the temp dir
+fn config(name: &str, text: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("beetree-config-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("beetree.toml");
+    fs::write(&path, text).unwrap();
+    path
+}
+
+#[test]
+fn reads_sections_and_dotted_keys_in_file_order() {
+    let path = config(
+        "keys",
+        r#"
+# the app
+languages = ["en", "de"]
+format = 'json'
+
+[llm]
+model = "gpt-4o-mini"
+temperature = 0.0
+max_tokens = 512
+headers = { "X-Team" = "web", X-Run = 7 }
+stream = true
+"#,
+    );
+    let file = ConfigFile::load(&path).unwrap();
+    assert_eq!(
+        file.values,
+        [
+            ("languages", "en,de".to_owned()),
+            ("serializer", "json".to_owned()),
+            ("model", "gpt-4o-mini".to_owned()),
+            ("temperature", "0".to_owned()),
+            ("max_tokens", "512".to_owned()),
+            ("headers", "X-Team: web\nX-Run: 7".to_owned()),
+            ("stream", "true".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn resolves_paths_relative_to_the_file() {
+    let path = config(
+        "paths",
+        "base = \"web/lang\"\nllm.glossary = \"terms.csv\"\n",
+    );
+    let dir = path.parent().unwrap();
+    let file = ConfigFile::load(&path).unwrap();
+    let values: Vec<_> = file.values.iter().map(|(_, v)| Path::new(v)).collect();
+    assert_eq!(values, [dir.join("web/lang"), dir.join("terms.csv")]);
+}
+
+#[test]
+fn gives_every_path_of_a_list_on_its_own() {
+    let path = config("path-list", "llm.ca_cert = [\"a.pem\", \"certs/b.pem\"]\n");
+    let dir = path.parent().unwrap();
+    let file = ConfigFile::load(&path).unwrap();
+    let certs: Vec<_> = file
+        .get("ca_certs")
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    assert_eq!(certs, [dir.join("a.pem"), dir.join("certs/b.pem")]);
+}
+
+#[test]
+fn refuses_unknown_keys_secrets_and_bad_toml() {
+    let unknown = config("unknown", "[llm]\nmodle = \"x\"\n");
+    assert!(matches!(
+        ConfigFile::load(&unknown),
+        Err(Error::UnknownKey(_, key)) if key == "llm.modle"
+    ));
+    let secret = config("secret", "llm.api_key = \"sk-1\"\n");
+    assert!(matches!(ConfigFile::load(&secret), Err(Error::Secret(..))));
+    let table = config("table", "llm.model = { name = \"x\" }\n");
+    assert!(matches!(
+        ConfigFile::load(&table),
+        Err(Error::UnknownKey(..))
+    ));
+    let broken = config("broken", "languages = [\"en\"\n");
+    assert!(matches!(ConfigFile::load(&broken), Err(Error::Parse(..))));
+}
@@ -0,0 +1,90 @@
+use beetree::format::csv;
+use beetree::lang;
+use beetree::layout::Layout;
+use std::fs;
+use std::path::PathBuf;
+
+/// an empty base directory below the temp dir
+fn base(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join(format!("beetree-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    base
+}
+
+#[test]
+fn quotes_only_when_needed() {
+    assert_eq!(csv::write_row(&["a", "b c", "d,e"], ','), "a,b c,\"d,e\"");
+    assert_eq!(
+        csv::write_row(&["say \"hi\"", " pad", "two\nlines"], ','),
+        "\"say \"\"hi\"\"\",\" pad\",\"two\nlines\""
+    );
+    assert_eq!(csv::write_row(&["a,b", "c;d"], ';'), "a,b;\"c;d\"");
+}
+
+#[test]
+fn guards_formulas() {
+    let row = csv::write_row(&["=SUM(A1)", "+1", "-brand", "@me", "'tis", "a=b"], ',');
+    assert_eq!(row, "'=SUM(A1),'+1,'-brand,'@me,'tis,a=b");
+    assert_eq!(
+        csv::parse(&row, ',').unwrap(),
+        [["=SUM(A1)", "+1", "-brand", "@me", "'tis", "a=b"]]
+    );
+    // spreadsheets drop the `'` when they save
+    assert_eq!(csv::parse("=1+1\n", ',').unwrap(), [["=1+1"]]);
+}
+
+#[test]
+fn keeps_quotes_in_front_of_formulas_on_a_round_trip() {
+    let fields = ["'=foo", "''+1", "'tis", "''", "'"];
+    let row = csv::write_row(&fields, ',');
+    assert_eq!(row, "''=foo,'''+1,'tis,'','");
+    assert_eq!(csv::parse(&row, ',').unwrap(), [fields]);
+}
+
+#[test]
+fn parses_quoted_fields_over_lines() {
+    let text = "file,key\r\napp,\"two\r\nlines, \"\"quoted\"\"\"\n";
+    assert_eq!(
+        csv::parse(text, ',').unwrap(),
+        [vec!["file", "key"], vec!["app", "two\r\nlines, \"quoted\""]]
+    );
+    assert!(csv::parse("a,\"open\n", ',').is_err());
+}
+
+#[test]
+fn detects_the_delimiter() {
+    assert_eq!(csv::delimiter("file;key;en\n"), ';');
+    assert_eq!(csv::delimiter("file\tkey\ten\n"), '\t');
+    assert_eq!(csv::delimiter("file\n"), ',');
+}
+
+#[test]
+fn imports_exact_keys_into_new_files() {
+    let base = base("csv-import");
+    fs::create_dir_all(base.join("en")).unwrap();
+    fs::write(base.join("en/lang.env"), "AB=\"ab\"\nA=\"a\"\n").unwrap();
+    let input = base.join("edit.csv");
+    fs::write(
+        &input,
+        "file,key,en,de\nlang.env,A,a new,'=a\nlang.env,AB,,ab de\n",
+    )
+    .unwrap();
+    lang::import_csv(lang::ImportCsvConfig {
+        verbose: false,
+        base_path: base.clone(),
+        layout: "{base}/{lang}/{file}".parse::<Layout>().unwrap(),
+        input,
+        yes: true,
+        serializer: None,
+    })
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(base.join("en/lang.env")).unwrap(),
+        "AB=\"ab\"\nA=\"a new\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(base.join("de/lang.env")).unwrap(),
+        "A=\"=a\"\nAB=\"ab de\"\n"
+    );
+}
@@ -0,0 +1,42 @@
+use beetree::lang::format_lang_text;
+
+#[test]
+fn sorts_and_quotes() {
+    let text = "B=b\nA='a'\n\n\nC=\"c\"\n";
+    assert_eq!(
+        format_lang_text(text, false).unwrap(),
+        "A=\"a\"\nB=\"b\"\nC=\"c\"\n"
+    );
+}
+
+#[test]
+fn keeps_multi_line_values() {
+    let text = "F=\"f\"\nE=\"multi\nline\"\nD=d\n";
+    assert_eq!(
+        format_lang_text(text, false).unwrap(),
+        "D=\"d\"\nE=\"multi\nline\"\nF=\"f\"\n"
+    );
+}
+
+#[test]
+fn refuses_unclosed_quotes() {
+    assert!(format_lang_text("A=\"open\nB=b\n", false).is_err());
+}
+
+#[test]
+fn keeps_file_header_on_top() {
+    let text = "# translations of the app\n\n# the b\nB=b\nA=a\n";
+    assert_eq!(
+        format_lang_text(text, false).unwrap(),
+        "# translations of the app\n\nA=\"a\"\n# the b\nB=\"b\"\n"
+    );
+}
+
+#[test]
+fn sorts_inside_sections() {
+    let text = "# header\nB=b\nA=a\n# second\nD=d\nC=c\n";
+    assert_eq!(
+        format_lang_text(text, true).unwrap(),
+        "# header\nA=\"a\"\nB=\"b\"\n\n# second\nC=\"c\"\nD=\"d\"\n"
+    );
+}
@@ -0,0 +1,113 @@
+use beetree::format::{AndroidFormat, Error, FileFormat, LangFormat};
+
+const TEXT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <!-- the title -->
+    <string name="title">It\'s   \"here\"</string>
+    <string name="welcome">Hello <xliff:g id="name" example="Bob">%1$s</xliff:g>, <b>welcome</b> &amp; more</string>
+    <string name="app_id" translatable="false">com.example</string>
+    <plurals name="emails">
+        <item quantity="one">%d email</item>
+        <item quantity="other">%d emails</item>
+    </plurals>
+    <string-array name="days">
+        <item>Mon</item>
+        <item>Tue</item>
+    </string-array>
+</resources>
+"#;
+
+#[test]
+fn finds_strings_and_items() {
+    assert_eq!(AndroidFormat.find(TEXT, "title"), Some(3));
+    assert_eq!(AndroidFormat.find(TEXT, "emails.other"), Some(8));
+    assert_eq!(AndroidFormat.find(TEXT, "days.1"), Some(12));
+    assert_eq!(AndroidFormat.find(TEXT, "tit"), None);
+}
+
+#[test]
+fn lists_entries_with_markup_kept() {
+    assert_eq!(
+        AndroidFormat.entries(TEXT).unwrap(),
+        [
+            ("title".to_owned(), "It's \"here\"".to_owned()),
+            (
+                "welcome".to_owned(),
+                r#"Hello <xliff:g id="name" example="Bob">%1$s</xliff:g>, <b>welcome</b> & more"#
+                    .to_owned()
+            ),
+            ("emails.one".to_owned(), "%d email".to_owned()),
+            ("emails.other".to_owned(), "%d emails".to_owned()),
+            ("days.0".to_owned(), "Mon".to_owned()),
+            ("days.1".to_owned(), "Tue".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn replaces_without_escaping_markup() {
+    let value =
+        r#"Hallo <xliff:g id="name" example="Bob">%1$s</xliff:g>, <b>willkommen</b> & 1 < 2"#;
+    let text = AndroidFormat.replace(TEXT, "welcome", value).unwrap();
+    assert!(text.contains(
+        r#"<string name="welcome">Hallo <xliff:g id="name" example="Bob">%1$s</xliff:g>, <b>willkommen</b> &amp; 1 &lt; 2</string>"#
+    ));
+    let entries = AndroidFormat.entries(&text).unwrap();
+    assert_eq!(entries[1].1, value);
+}
+
+#[test]
+fn escapes_for_aapt() {
+    let text = AndroidFormat
+        .replace(TEXT, "title", "@it's \"a\"\nnew  line")
+        .unwrap();
+    assert!(text.contains(r#"<string name="title">"\@it\'s \"a\"\nnew  line"</string>"#));
+    let entries = AndroidFormat.entries(&text).unwrap();
+    assert_eq!(entries[0].1, "@it's \"a\"\nnew  line");
+}
+
+#[test]
+fn replaces_plurals_and_arrays_as_a_whole() {
+    let text = AndroidFormat
+        .replace(TEXT, "emails", "%d Mail|%d Mails")
+        .unwrap();
+    assert!(text.contains("<item quantity=\"one\">%d Mail</item>"));
+    assert!(text.contains("<item quantity=\"other\">%d Mails</item>"));
+}
+
+#[test]
+fn inserts_appends_and_removes() {
+    let text = AndroidFormat.insert(TEXT, "title", "intro", "Hi").unwrap();
+    assert!(text.contains("    <string name=\"intro\">Hi</string>\n    <!-- the title -->"));
+    let text = AndroidFormat.append(TEXT, "days.2", "Wed").unwrap();
+    assert!(text.contains("<item>Tue</item>\n        <item>Wed</item>"));
+    let text = AndroidFormat.append(TEXT, "bye", "Bye").unwrap();
+    assert!(
+        text.contains("    </string-array>\n    <string name=\"bye\">Bye</string>\n</resources>")
+    );
+    let text = AndroidFormat.remove(TEXT, "title").unwrap();
+    assert!(!text.contains("the title"));
+    assert!(text.contains("<resources xmlns:xliff=\"urn:oasis:names:tc:xliff:document:1.2\">\n    <string name=\"welcome\">"));
+    assert!(matches!(
+        AndroidFormat.append(TEXT, "title", "x"),
+        Err(Error::KeyExists(_))
+    ));
+}
+
+#[test]
+fn detects_only_resource_xml_files() {
+    assert_eq!(
+        FileFormat::from_path("res/values-de/strings.xml"),
+        FileFormat::Android
+    );
+    assert_eq!(
+        FileFormat::from_path("res/values/arrays.xml"),
+        FileFormat::Android
+    );
+    assert_eq!(FileFormat::from_path("strings.xml"), FileFormat::Android);
+    assert_ne!(FileFormat::from_path("pom.xml"), FileFormat::Android);
+    assert_ne!(
+        FileFormat::from_path("de/messages.xml"),
+        FileFormat::Android
+    );
+}
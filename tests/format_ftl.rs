@@ -0,0 +1,144 @@
+use beetree::format::{Error, FtlFormat, LangFormat};
+
+const TEXT: &str = "\
+# the app name
+-brand = Beetree
+
+hello = Hello { $name }
+    .title = Greeting
+
+emails =
+    { $count ->
+        [one] one email
+       *[other] { $count } emails
+    }
+";
+
+#[test]
+fn finds_messages_terms_and_attributes() {
+    assert_eq!(FtlFormat.find(TEXT, "-brand"), Some(1));
+    assert_eq!(FtlFormat.find(TEXT, "hello"), Some(3));
+    assert_eq!(FtlFormat.find(TEXT, "hello.title"), Some(4));
+    assert_eq!(FtlFormat.find(TEXT, "emails"), Some(6));
+    assert_eq!(FtlFormat.find(TEXT, "hell"), None);
+    assert_eq!(FtlFormat.find(TEXT, "hello.tooltip"), None);
+}
+
+#[test]
+fn lists_entries_with_attributes_and_selects() {
+    assert_eq!(
+        FtlFormat.entries(TEXT).unwrap(),
+        [
+            ("-brand".to_owned(), "Beetree".to_owned()),
+            ("hello".to_owned(), "Hello { $name }".to_owned()),
+            ("hello.title".to_owned(), "Greeting".to_owned()),
+            (
+                "emails".to_owned(),
+                "{ $count ->\n    [one] one email\n   *[other] { $count } emails\n}".to_owned()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn inserts_before_the_attached_comment() {
+    let text = FtlFormat.insert(TEXT, "-brand", "intro", "Hi").unwrap();
+    assert!(text.starts_with("intro = Hi\n# the app name\n-brand"));
+    let text = FtlFormat.insert(TEXT, "emails", "bye", "Bye").unwrap();
+    assert!(text.contains("    .title = Greeting\n\nbye = Bye\n\nemails ="));
+    assert!(matches!(
+        FtlFormat.insert(TEXT, "hello", "emails", "x"),
+        Err(Error::KeyExists(_))
+    ));
+}
+
+#[test]
+fn appends_messages_and_attributes() {
+    let text = FtlFormat.append(TEXT, "bye", "Bye").unwrap();
+    assert!(text.ends_with("    }\n\nbye = Bye"));
+    let text = FtlFormat.append(TEXT, "hello.aria", "Hi there").unwrap();
+    assert!(text.contains("    .title = Greeting\n    .aria = Hi there\n"));
+    assert!(matches!(
+        FtlFormat.append(TEXT, "hello.title", "x"),
+        Err(Error::KeyExists(_))
+    ));
+}
+
+#[test]
+fn removes_messages_with_comments_and_attributes() {
+    let text = FtlFormat.remove(TEXT, "-brand").unwrap();
+    assert!(text.starts_with("hello = "));
+    let text = FtlFormat.remove(TEXT, "hello").unwrap();
+    assert!(text.contains("-brand = Beetree\n\nemails ="));
+    let text = FtlFormat.remove(TEXT, "hello.title").unwrap();
+    assert!(text.contains("hello = Hello { $name }\n\nemails ="));
+    assert!(matches!(
+        FtlFormat.remove(TEXT, "nope"),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn replaces_values_keeping_attributes() {
+    let text = FtlFormat.replace(TEXT, "hello", "Hallo { $name }").unwrap();
+    assert!(text.contains("hello = Hallo { $name }\n    .title = Greeting\n"));
+    let text = FtlFormat.replace(TEXT, "hello.title", "Gruß").unwrap();
+    assert!(text.contains("    .title = Gruß\n"));
+}
+
+#[test]
+fn lays_out_select_variants_on_their_own_lines() {
+    let text = FtlFormat
+        .replace(
+            TEXT,
+            "emails",
+            "{ $count -> [one] eine Mail *[other] { $count } Mails }",
+        )
+        .unwrap();
+    assert!(text.contains(
+        "emails =\n    { $count ->\n        [one] eine Mail\n       *[other] { $count } Mails\n    }"
+    ));
+    let entries = FtlFormat.entries(&text).unwrap();
+    assert_eq!(
+        entries[3].1,
+        "{ $count ->\n    [one] eine Mail\n   *[other] { $count } Mails\n}"
+    );
+}
+
+#[test]
+fn breaks_only_before_variant_keys() {
+    let text = FtlFormat
+        .replace(
+            TEXT,
+            "emails",
+            "{ $count ->[0] keine *[other] see [the docs] or a[b] }",
+        )
+        .unwrap();
+    assert!(
+        text.contains(
+            "    { $count ->\n        [0] keine\n       *[other] see [the docs] or a[b]\n    }"
+        ),
+        "{text}"
+    );
+    let text = FtlFormat
+        .replace(
+            TEXT,
+            "emails",
+            "{ $count ->\n    [one] one, see [docs]\n   *[other] see [docs]\n}",
+        )
+        .unwrap();
+    assert!(
+        text.contains(
+            "    { $count ->\n        [one] one, see [docs]\n       *[other] see [docs]\n    }"
+        ),
+        "{text}"
+    );
+}
+
+#[test]
+fn round_trips_multiline_values() {
+    let text = FtlFormat.replace(TEXT, "-brand", "first\nsecond").unwrap();
+    assert!(text.contains("-brand =\n    first\n    second\n"));
+    let entries = FtlFormat.entries(&text).unwrap();
+    assert_eq!(entries[0].1, "first\nsecond");
+}
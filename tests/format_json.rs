@@ -0,0 +1,158 @@
+use beetree::format::{Error, JsonFormat, LangFormat};
+
+const NESTED: &str = r#"{
+  "home": {
+    "title": "Home",
+    "intro": "Welcome"
+  },
+  "bye": "Bye"
+}"#;
+const FLAT: &str = r#"{
+    "home.title": "Home",
+    "bye": "Bye"
+}"#;
+
+#[test]
+fn finds_dot_paths() {
+    assert_eq!(JsonFormat.find(NESTED, "home.intro"), Some(3));
+    assert_eq!(JsonFormat.find(NESTED, "bye"), Some(5));
+    assert_eq!(JsonFormat.find(FLAT, "home.title"), Some(1));
+    assert_eq!(JsonFormat.find(NESTED, "home.missing"), None);
+    assert_eq!(JsonFormat.find(NESTED, "hom"), None);
+}
+
+#[test]
+fn lists_entries() {
+    assert_eq!(
+        JsonFormat.entries(NESTED).unwrap(),
+        [
+            ("home.title".to_owned(), "Home".to_owned()),
+            ("home.intro".to_owned(), "Welcome".to_owned()),
+            ("bye".to_owned(), "Bye".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn skips_values_that_are_no_strings() {
+    let text = r#"{"count": 3, "list": ["a"], "ok": true, "text": "t"}"#;
+    assert_eq!(
+        JsonFormat.entries(text).unwrap(),
+        [("text".to_owned(), "t".to_owned())]
+    );
+}
+
+#[test]
+fn inserts_next_to_a_nested_key() {
+    let text = JsonFormat
+        .insert(NESTED, "home.intro", "home.sub", "Sub")
+        .unwrap();
+    assert_eq!(
+        text,
+        r#"{
+  "home": {
+    "title": "Home",
+    "sub": "Sub",
+    "intro": "Welcome"
+  },
+  "bye": "Bye"
+}"#
+    );
+}
+
+#[test]
+fn refuses_to_insert_under_another_parent() {
+    let error = JsonFormat
+        .insert(NESTED, "home.intro", "other.sub", "Sub")
+        .unwrap_err();
+    assert!(matches!(error, Error::OtherParent(..)));
+    let error = JsonFormat
+        .insert(NESTED, "home.intro", "home.title", "Title")
+        .unwrap_err();
+    assert!(matches!(error, Error::KeyExists(..)));
+}
+
+#[test]
+fn appends_nested_objects() {
+    let text = JsonFormat.append(NESTED, "menu.open", "Open").unwrap();
+    assert_eq!(
+        text,
+        r#"{
+  "home": {
+    "title": "Home",
+    "intro": "Welcome"
+  },
+  "bye": "Bye",
+  "menu": {
+    "open": "Open"
+  }
+}"#
+    );
+    let text = JsonFormat.append(NESTED, "home.outro", "Later").unwrap();
+    assert!(text.contains("\"intro\": \"Welcome\",\n    \"outro\": \"Later\"\n  },"));
+}
+
+#[test]
+fn keeps_flat_files_flat() {
+    let text = JsonFormat.append(FLAT, "menu.open", "Open").unwrap();
+    assert_eq!(
+        text,
+        r#"{
+    "home.title": "Home",
+    "bye": "Bye",
+    "menu.open": "Open"
+}"#
+    );
+}
+
+#[test]
+fn appends_to_empty_objects() {
+    assert_eq!(
+        JsonFormat.append("{}", "a", "A").unwrap(),
+        "{\n  \"a\": \"A\"\n}"
+    );
+}
+
+#[test]
+fn removes_members() {
+    assert_eq!(
+        JsonFormat.remove(NESTED, "bye").unwrap(),
+        r#"{
+  "home": {
+    "title": "Home",
+    "intro": "Welcome"
+  }
+}"#
+    );
+    let text = JsonFormat.remove(NESTED, "home.title").unwrap();
+    assert!(text.contains("\"home\": {\n    \"intro\""));
+    assert!(matches!(
+        JsonFormat.remove(NESTED, "nope"),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn replaces_and_escapes_values() {
+    let value = "say \"hi\"\n\\ é";
+    let text = JsonFormat.replace(NESTED, "home.title", value).unwrap();
+    assert!(text.contains(r#""title": "say \"hi\"\n\\ é","#));
+    assert_eq!(JsonFormat.entries(&text).unwrap()[0].1, value);
+}
+
+#[test]
+fn reads_escaped_keys_and_values() {
+    let text = r#"{"a\"b": "é\n"}"#;
+    assert_eq!(
+        JsonFormat.entries(text).unwrap(),
+        [("a\"b".to_owned(), "é\n".to_owned())]
+    );
+}
+
+#[test]
+fn reports_parse_errors_with_their_line() {
+    let error = JsonFormat
+        .entries("{\n  \"a\": \"b\"\n  \"c\": \"d\"\n}")
+        .unwrap_err();
+    assert!(matches!(error, Error::Parse { line: 3, .. }));
+}
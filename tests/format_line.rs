@@ -0,0 +1,78 @@
+use beetree::format::{Error, LangFormat, LineFormat};
+use beetree::serializer::ValueSerializer;
+
+const DOTENV: LineFormat = LineFormat {
+    serializer: ValueSerializer::Dotenv,
+};
+const JS: LineFormat = LineFormat {
+    serializer: ValueSerializer::Js,
+};
+
+const TEXT: &str = "\
+# keys
+AB=\"ab\"
+export A='a'
+ABC=abc
+";
+
+#[test]
+fn finds_exact_keys_only() {
+    assert_eq!(DOTENV.find(TEXT, "A"), Some(2));
+    assert_eq!(DOTENV.find(TEXT, "AB"), Some(1));
+    assert_eq!(DOTENV.find(TEXT, "ABC"), Some(3));
+    assert_eq!(DOTENV.find(TEXT, "ABCD"), None);
+    assert_eq!(DOTENV.find(TEXT, "keys"), None);
+}
+
+#[test]
+fn edits_the_exact_key() {
+    let text = DOTENV.replace(TEXT, "A", "new").unwrap();
+    assert!(text.contains("AB=\"ab\"\nexport A=\"new\"\nABC=abc"));
+    let text = DOTENV.remove(TEXT, "A").unwrap();
+    assert!(text.contains("AB=\"ab\"\nABC=abc"));
+    let text = DOTENV.insert(TEXT, "ABC", "B", "b").unwrap();
+    assert!(text.contains("export A='a'\nB=\"b\"\nABC=abc"));
+    assert!(matches!(
+        DOTENV.remove(TEXT, "ABCD"),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn lists_entries_of_js_objects() {
+    let text = "const messages = {\n  \"home\": \"Home\",\n  // note\n  bye: 'Bye',\n};\n";
+    assert_eq!(
+        JS.entries(text).unwrap(),
+        [
+            ("home".to_owned(), "Home".to_owned()),
+            ("bye".to_owned(), "Bye".to_owned()),
+        ]
+    );
+    assert_eq!(JS.find(text, "home"), Some(1));
+    assert_eq!(JS.find(text, "messages"), None);
+}
+
+#[test]
+fn appends_after_the_last_line() {
+    assert_eq!(DOTENV.append("A=a", "B", "b").unwrap(), "A=a\nB=\"b\"");
+    assert_eq!(DOTENV.append("", "B", "b").unwrap(), "B=\"b\"");
+}
+
+#[test]
+fn keeps_the_indent_and_the_trailing_comma() {
+    let text = "const messages = {\n  \"home\": 'Home', // start\n  bye: \"Bye\"\n};\n";
+    let text = JS.replace(text, "home", "Start").unwrap();
+    assert!(
+        text.contains("\n  \"home\": \"Start\", // start\n"),
+        "{text}"
+    );
+    let text = JS.replace(&text, "bye", "Dag").unwrap();
+    assert!(text.contains("\n  bye: \"Dag\"\n};"), "{text}");
+    let text = JS.insert(&text, "bye", "open", "Open").unwrap();
+    assert!(
+        text.contains("\n  open: \"Open\",\n  bye: \"Dag\"\n"),
+        "{text}"
+    );
+    let text = DOTENV.replace("  A = 'a' # note\n", "A", "b").unwrap();
+    assert_eq!(text, "  A = \"b\" # note");
+}
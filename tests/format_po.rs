@@ -0,0 +1,161 @@
+use beetree::format::po::{self, PoFormat};
+use beetree::format::{Error, LangFormat};
+
+const TEXT: &str = r#"msgid ""
+msgstr ""
+"Language: nl\n"
+
+#: src/main.rs:1
+msgid "Hello"
+msgstr "Hallo"
+
+msgctxt "menu"
+msgid "Open"
+msgstr ""
+
+msgid "One file"
+msgid_plural "%d files"
+msgstr[0] "Een bestand"
+msgstr[1] "%d bestanden"
+"#;
+
+const TEMPLATE: &str = r#"msgid ""
+msgstr ""
+"Project-Id-Version: app\n"
+"Language: \n"
+"Plural-Forms: nplurals=INTEGER; plural=EXPRESSION;\n"
+
+msgid "One file"
+msgid_plural "%d files"
+msgstr[0] ""
+msgstr[1] ""
+"#;
+
+#[test]
+fn finds_by_msgid_and_context() {
+    assert_eq!(PoFormat.find(TEXT, "Hello"), Some(5));
+    assert_eq!(PoFormat.find(TEXT, "menu|Open"), Some(8));
+    assert_eq!(PoFormat.find(TEXT, "Open"), None);
+    assert_eq!(PoFormat.find(TEXT, "Hell"), None);
+}
+
+#[test]
+fn lists_entries_without_the_header() {
+    assert_eq!(
+        PoFormat.entries(TEXT).unwrap(),
+        [
+            ("Hello".to_owned(), "Hallo".to_owned()),
+            ("menu|Open".to_owned(), String::new()),
+            ("One file".to_owned(), "Een bestand|%d bestanden".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn reads_multi_line_strings() {
+    let text = "msgid \"\"\n\"long \"\n\"id\"\nmsgstr \"a\\n\"\n\"b\\\"\"\n";
+    assert_eq!(
+        PoFormat.entries(text).unwrap(),
+        [("long id".to_owned(), "a\nb\"".to_owned())]
+    );
+}
+
+#[test]
+fn inserts_appends_and_removes_entries() {
+    let text = PoFormat.insert(TEXT, "Hello", "ctx|Bye", "Dag").unwrap();
+    assert!(text.contains("msgctxt \"ctx\"\nmsgid \"Bye\"\nmsgstr \"Dag\"\n\n#: src/main.rs:1\n"));
+    let text = PoFormat.append(TEXT, "Bye", "Dag").unwrap();
+    assert!(text.ends_with("msgstr[1] \"%d bestanden\"\n\nmsgid \"Bye\"\nmsgstr \"Dag\""));
+    let text = PoFormat.remove(TEXT, "Hello").unwrap();
+    assert!(text.contains("\"Language: nl\\n\"\n\nmsgctxt \"menu\""));
+    assert!(matches!(
+        PoFormat.append(TEXT, "Hello", "x"),
+        Err(Error::KeyExists(_))
+    ));
+}
+
+#[test]
+fn replaces_plural_forms_and_escapes() {
+    let text = PoFormat
+        .replace(TEXT, "One file", "Eén \"bestand\"|%d\tbestanden")
+        .unwrap();
+    assert!(text.contains("msgstr[0] \"Eén \\\"bestand\\\"\"\nmsgstr[1] \"%d\\tbestanden\""));
+    let text = PoFormat.replace(TEXT, "menu|Open", "Openen").unwrap();
+    assert_eq!(PoFormat.entries(&text).unwrap()[1].1, "Openen");
+}
+
+#[test]
+fn escapes_bars_in_keys_and_plural_forms() {
+    let text = "msgid \"Home | Settings\"\nmsgstr \"\"\n\nmsgid \"a\"\nmsgid_plural \"as\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\n";
+    assert_eq!(PoFormat.find(text, "Home \\| Settings"), Some(0));
+    let text = PoFormat
+        .replace(text, "Home \\| Settings", "Start | Instellingen")
+        .unwrap();
+    let text = PoFormat.replace(&text, "a", "één \\| x|b\\\\").unwrap();
+    assert!(text.contains("msgstr[0] \"één | x\"\nmsgstr[1] \"b\\\\\""));
+    assert_eq!(
+        PoFormat.entries(&text).unwrap(),
+        [
+            (
+                "Home \\| Settings".to_owned(),
+                "Start | Instellingen".to_owned()
+            ),
+            ("a".to_owned(), "één \\| x|b\\\\".to_owned()),
+        ]
+    );
+    assert_eq!(po::split("a\\|b|c"), ["a|b", "c"]);
+    let text = PoFormat.append("", "x\\|y|z", "v").unwrap();
+    assert_eq!(text, "msgctxt \"x|y\"\nmsgid \"z\"\nmsgstr \"v\"");
+}
+
+#[test]
+fn fills_only_empty_forms() {
+    let text = "msgid \"a\"\nmsgid_plural \"as\"\nmsgstr[0] \"een\"\nmsgstr[1] \"\"\n";
+    let text = po::fill(text, "a", "one", "many").unwrap();
+    assert_eq!(
+        text,
+        "msgid \"a\"\nmsgid_plural \"as\"\nmsgstr[0] \"een\"\nmsgstr[1] \"many\""
+    );
+}
+
+#[test]
+fn leaves_extra_plural_forms_empty_and_fuzzy() {
+    let text = "#: a.c:1\nmsgid \"a\"\nmsgid_plural \"as\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\nmsgstr[2] \"\"\n";
+    let text = po::fill(text, "a", "one", "many").unwrap();
+    assert_eq!(
+        text,
+        "#: a.c:1\n#, fuzzy\nmsgid \"a\"\nmsgid_plural \"as\"\nmsgstr[0] \"one\"\nmsgstr[1] \"many\"\nmsgstr[2] \"\""
+    );
+    let entries = po::parse(&text).unwrap();
+    assert!(entries[0].is_fuzzy());
+    let text = "#, c-format\nmsgid \"a\"\nmsgid_plural \"as\"\nmsgstr[0] \"\"\nmsgstr[1] \"\"\nmsgstr[2] \"\"\n";
+    let text = po::fill(text, "a", "one", "many").unwrap();
+    assert!(text.starts_with("#, c-format, fuzzy\nmsgid"));
+}
+
+#[test]
+fn creates_catalogs_with_the_plural_forms_of_the_language() {
+    let polish = po::from_template(TEMPLATE, "pl").unwrap();
+    assert!(polish.contains("\"Language: pl\\n\"\n\"Plural-Forms: nplurals=3; plural=(n==1 ? 0"));
+    assert!(polish.ends_with("msgstr[0] \"\"\nmsgstr[1] \"\"\nmsgstr[2] \"\"\n"));
+    let arabic = po::from_template(TEMPLATE, "ar_EG").unwrap();
+    assert!(arabic.contains("\"Plural-Forms: nplurals=6;"));
+    assert!(arabic.contains("msgstr[5] \"\"\n"));
+    let japanese = po::from_template(TEMPLATE, "ja").unwrap();
+    assert!(japanese.ends_with("msgid_plural \"%d files\"\nmsgstr[0] \"\"\n"));
+}
+
+#[test]
+fn keeps_the_template_forms_of_unknown_languages() {
+    let text = po::from_template(TEMPLATE, "xx").unwrap();
+    assert!(text.contains("\"Language: xx\\n\"\n\"Plural-Forms: nplurals=INTEGER;"));
+    assert!(text.ends_with("msgstr[0] \"\"\nmsgstr[1] \"\"\n"));
+}
+
+#[test]
+fn adds_a_missing_header() {
+    let text = po::from_template("msgid \"a\"\nmsgstr \"\"\n", "fr").unwrap();
+    assert!(text.starts_with(
+        "msgid \"\"\nmsgstr \"\"\n\"Language: fr\\n\"\n\"Plural-Forms: nplurals=2; plural=(n > 1);\\n\"\n\nmsgid \"a\""
+    ));
+}
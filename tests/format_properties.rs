@@ -0,0 +1,85 @@
+use beetree::format::{Error, LangFormat, PropertiesFormat};
+
+const TEXT: &str = "\
+# greetings
+! also a comment
+hello = Hallo
+home.title:Start
+long.text = first \\
+    second
+escaped\\ key=v
+colon\\:key value
+";
+
+#[test]
+fn finds_unescaped_keys() {
+    assert_eq!(PropertiesFormat.find(TEXT, "hello"), Some(2));
+    assert_eq!(PropertiesFormat.find(TEXT, "long.text"), Some(4));
+    assert_eq!(PropertiesFormat.find(TEXT, "escaped key"), Some(6));
+    assert_eq!(PropertiesFormat.find(TEXT, "colon:key"), Some(7));
+    assert_eq!(PropertiesFormat.find(TEXT, "hell"), None);
+}
+
+#[test]
+fn lists_entries_with_continuations() {
+    assert_eq!(
+        PropertiesFormat.entries(TEXT).unwrap(),
+        [
+            ("hello".to_owned(), "Hallo".to_owned()),
+            ("home.title".to_owned(), "Start".to_owned()),
+            ("long.text".to_owned(), "first second".to_owned()),
+            ("escaped key".to_owned(), "v".to_owned()),
+            ("colon:key".to_owned(), "value".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn inserts_and_appends_escaped_entries() {
+    let text = PropertiesFormat
+        .insert(TEXT, "home.title", "new key", "été")
+        .unwrap();
+    assert!(text.contains("hello = Hallo\nnew\\ key=\\u00E9t\\u00E9\nhome.title"));
+    let text = PropertiesFormat.append(TEXT, "z", "=last").unwrap();
+    assert!(text.ends_with("colon\\:key value\nz=\\=last"));
+    assert!(matches!(
+        PropertiesFormat.append(TEXT, "hello", "x"),
+        Err(Error::KeyExists(_))
+    ));
+}
+
+#[test]
+fn removes_continuation_lines() {
+    let text = PropertiesFormat.remove(TEXT, "long.text").unwrap();
+    assert!(text.contains("home.title:Start\nescaped\\ key=v"));
+    assert!(matches!(
+        PropertiesFormat.remove(TEXT, "nope"),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn replaces_keeping_key_and_separator() {
+    let text = PropertiesFormat.replace(TEXT, "hello", "Hoi\n!").unwrap();
+    assert!(text.contains("hello = Hoi\\n!\n"));
+    let text = PropertiesFormat.replace(TEXT, "long.text", "one").unwrap();
+    assert!(text.contains("long.text = one\nescaped"));
+    let text = PropertiesFormat.replace(TEXT, "colon:key", "x").unwrap();
+    assert!(text.contains("colon\\:key x"));
+}
+
+#[test]
+fn round_trips_values() {
+    for value in [
+        " lead",
+        "trail ",
+        "tab\there",
+        "😀",
+        "a=b:c#d!e",
+        "back\\slash",
+    ] {
+        let text = PropertiesFormat.replace(TEXT, "hello", value).unwrap();
+        let entries = PropertiesFormat.entries(&text).unwrap();
+        assert_eq!(entries[0].1, value, "{text}");
+    }
+}
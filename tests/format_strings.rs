@@ -0,0 +1,88 @@
+use beetree::file_handling::{self, Encoding};
+use beetree::format::{Error, LangFormat, StringsFormat};
+use std::fs;
+
+const TEXT: &str = r#"/* the title */
+"title" = "Hello \"you\"";
+
+"multi" = "one\ntwo";
+bare_key = "bare";
+// the bye
+"bye" = "Bye\U00E9";
+"#;
+
+#[test]
+fn finds_quoted_and_bare_keys() {
+    assert_eq!(StringsFormat.find(TEXT, "title"), Some(1));
+    assert_eq!(StringsFormat.find(TEXT, "bare_key"), Some(4));
+    assert_eq!(StringsFormat.find(TEXT, "bye"), Some(6));
+    assert_eq!(StringsFormat.find(TEXT, "by"), None);
+}
+
+#[test]
+fn lists_unescaped_entries() {
+    assert_eq!(
+        StringsFormat.entries(TEXT).unwrap(),
+        [
+            ("title".to_owned(), "Hello \"you\"".to_owned()),
+            ("multi".to_owned(), "one\ntwo".to_owned()),
+            ("bare_key".to_owned(), "bare".to_owned()),
+            ("bye".to_owned(), "Byeé".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn edits_entries_with_their_comments() {
+    let text = StringsFormat.insert(TEXT, "bye", "hi", "Hi").unwrap();
+    assert!(text.contains("bare_key = \"bare\";\n\"hi\" = \"Hi\";\n// the bye"));
+    let text = StringsFormat.append(TEXT, "end", "tab\there").unwrap();
+    assert!(text.ends_with("\"bye\" = \"Bye\\U00E9\";\n\n\"end\" = \"tab\\there\";"));
+    let text = StringsFormat.remove(TEXT, "title").unwrap();
+    assert!(text.starts_with("\"multi\""));
+    let text = StringsFormat.replace(TEXT, "multi", "a \"b\"\\c").unwrap();
+    assert!(text.contains("\"multi\" = \"a \\\"b\\\"\\\\c\";"));
+    assert!(matches!(
+        StringsFormat.insert(TEXT, "nope", "x", "x"),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn refuses_broken_files() {
+    assert!(StringsFormat.entries("\"a\" = \"b\"").is_err());
+    assert!(StringsFormat.entries("\"a\" = \"b;").is_err());
+    assert!(StringsFormat.entries("/* open").is_err());
+}
+
+#[test]
+fn reads_and_writes_utf16_files() {
+    let dir = std::env::temp_dir().join(format!("beetree-strings-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let text = "\"bye\" = \"Tschüß\";\n";
+    for (name, bytes) in [
+        (
+            "le_bom",
+            [vec![0xff, 0xfe], utf16(text, u16::to_le_bytes)].concat(),
+        ),
+        (
+            "be_bom",
+            [vec![0xfe, 0xff], utf16(text, u16::to_be_bytes)].concat(),
+        ),
+        ("le", utf16(text, u16::to_le_bytes)),
+        ("be", utf16(text, u16::to_be_bytes)),
+    ] {
+        let path = dir.join(format!("{name}.strings"));
+        fs::write(&path, &bytes).unwrap();
+        let (style, read) = file_handling::read_text_file(&path).unwrap();
+        assert_ne!(style.encoding, Encoding::Utf8, "{name}");
+        assert_eq!(read, text, "{name}");
+        assert_eq!(StringsFormat.entries(&read).unwrap()[0].1, "Tschüß");
+        file_handling::write_text_file(&path, style, &read).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), bytes, "{name}");
+    }
+}
+
+fn utf16(text: &str, bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    text.encode_utf16().flat_map(bytes).collect()
+}
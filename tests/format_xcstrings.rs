@@ -0,0 +1,158 @@
+use beetree::format::{Error, LangFormat, XcstringsFormat};
+use beetree::lang;
+use beetree::layout::Layout;
+use std::fs;
+
+const TEXT: &str = r#"{
+  "sourceLanguage" : "en",
+  "strings" : {
+    "%lld emails" : {
+      "localizations" : {
+        "de" : {
+          "variations" : {
+            "plural" : {
+              "one" : {
+                "stringUnit" : {
+                  "state" : "translated",
+                  "value" : "%lld E-Mail"
+                }
+              },
+              "other" : {
+                "stringUnit" : {
+                  "state" : "translated",
+                  "value" : "%lld E-Mails"
+                }
+              }
+            }
+          }
+        }
+      }
+    },
+    "Bye" : {
+
+    },
+    "com.example" : {
+      "shouldTranslate" : false
+    },
+    "Hello" : {
+      "localizations" : {
+        "de" : {
+          "stringUnit" : {
+            "state" : "translated",
+            "value" : "Hallo"
+          }
+        },
+        "en" : {
+          "stringUnit" : {
+            "state" : "translated",
+            "value" : "Hello there"
+          }
+        }
+      }
+    }
+  },
+  "version" : "1.0"
+}
+"#;
+
+fn lang(lang: &str) -> XcstringsFormat {
+    XcstringsFormat { lang: lang.into() }
+}
+
+#[test]
+fn lists_the_entries_of_a_language() {
+    assert_eq!(
+        lang("de").entries(TEXT).unwrap(),
+        [
+            ("%lld emails.one".to_owned(), "%lld E-Mail".to_owned()),
+            ("%lld emails.other".to_owned(), "%lld E-Mails".to_owned()),
+            ("Hello".to_owned(), "Hallo".to_owned()),
+        ]
+    );
+    // the source language falls back to the key
+    assert_eq!(
+        lang("en").entries(TEXT).unwrap(),
+        [
+            ("%lld emails".to_owned(), "%lld emails".to_owned()),
+            ("Bye".to_owned(), "Bye".to_owned()),
+            ("Hello".to_owned(), "Hello there".to_owned()),
+        ]
+    );
+    assert!(lang("fr").entries(TEXT).unwrap().is_empty());
+}
+
+#[test]
+fn finds_the_line_of_the_key() {
+    assert_eq!(lang("de").find(TEXT, "Hello"), Some(31));
+    assert_eq!(lang("de").find(TEXT, "%lld emails.other"), Some(3));
+    assert_eq!(lang("de").find(TEXT, "Bye"), None);
+    assert_eq!(lang("en").find(TEXT, "Bye"), Some(25));
+}
+
+#[test]
+fn keeps_the_xcode_layout() {
+    let text = lang("de").replace(TEXT, "Hello", "Hallo").unwrap();
+    assert_eq!(text, TEXT);
+}
+
+#[test]
+fn sets_values_of_a_language() {
+    let text = lang("de").append(TEXT, "Bye", "Tschüss").unwrap();
+    assert!(text.contains(
+        "    \"Bye\" : {\n      \"localizations\" : {\n        \"de\" : {\n          \"stringUnit\" : {\n            \"state\" : \"translated\",\n            \"value\" : \"Tschüss\"\n"
+    ));
+    assert_eq!(lang("de").entries(&text).unwrap()[2].1, "Tschüss");
+    let text = lang("de")
+        .replace(TEXT, "%lld emails.one", "eine E-Mail")
+        .unwrap();
+    assert_eq!(lang("de").entries(&text).unwrap()[0].1, "eine E-Mail");
+    let text = lang("de")
+        .append(TEXT, "%lld emails.few", "%lld Mails")
+        .unwrap();
+    assert_eq!(lang("de").entries(&text).unwrap()[0].0, "%lld emails.few");
+    let text = lang("en").append(TEXT, "New", "New").unwrap();
+    assert!(text.contains("    \"New\" : {\n      \"extractionState\" : \"manual\",\n"));
+    assert!(matches!(
+        lang("de").append(TEXT, "Hello", "x"),
+        Err(Error::KeyExists(_))
+    ));
+    assert!(matches!(
+        lang("de").replace(TEXT, "Bye", "x"),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn removes_a_language_or_the_key() {
+    let text = lang("de").remove(TEXT, "Hello").unwrap();
+    assert!(lang("de").find(&text, "Hello").is_none());
+    assert_eq!(lang("en").entries(&text).unwrap()[2].1, "Hello there");
+    let text = lang("en").remove(TEXT, "Hello").unwrap();
+    assert!(!text.contains("\"Hello\""));
+}
+
+#[test]
+fn edits_a_shared_catalog_per_language() {
+    let base = std::env::temp_dir().join(format!("beetree-xcstrings-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    fs::write(base.join("Localizable.xcstrings"), TEXT).unwrap();
+    let input = base.join("edit.csv");
+    fs::write(
+        &input,
+        "file,key,en,de,fr\nLocalizable.xcstrings,Bye,,Tschüss,Salut\n",
+    )
+    .unwrap();
+    lang::import_csv(lang::ImportCsvConfig {
+        verbose: false,
+        base_path: base.clone(),
+        layout: Layout::default(),
+        input,
+        yes: true,
+        serializer: None,
+    })
+    .unwrap();
+    let text = fs::read_to_string(base.join("Localizable.xcstrings")).unwrap();
+    assert_eq!(lang("de").entries(&text).unwrap()[2].1, "Tschüss");
+    assert_eq!(lang("fr").entries(&text).unwrap()[0].1, "Salut");
+}
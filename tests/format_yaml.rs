@@ -0,0 +1,160 @@
+use beetree::format::{Error, LangFormat, YamlFormat};
+
+const WRAPPED: &str = "\
+nl:
+  # the start page
+  home:
+    title: Start
+    intro: \"Welkom: fijn\" # shown once
+  bye: Dag
+";
+
+#[test]
+fn finds_keys_inside_the_locale() {
+    assert_eq!(YamlFormat.find(WRAPPED, "home.title"), Some(3));
+    assert_eq!(YamlFormat.find(WRAPPED, "nl.bye"), Some(5));
+    assert_eq!(YamlFormat.find(WRAPPED, "home.missing"), None);
+}
+
+#[test]
+fn lists_entries_without_the_locale() {
+    assert_eq!(
+        YamlFormat.entries(WRAPPED).unwrap(),
+        [
+            ("home.title".to_owned(), "Start".to_owned()),
+            ("home.intro".to_owned(), "Welkom: fijn".to_owned()),
+            ("bye".to_owned(), "Dag".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn reads_scalar_styles() {
+    let text = "\
+a: 'it''s'
+b: |
+  line one
+  line two
+c: >
+  folded
+  text
+d: plain # comment
+e: [1, 2]
+";
+    assert_eq!(
+        YamlFormat.entries(text).unwrap(),
+        [
+            ("a".to_owned(), "it's".to_owned()),
+            ("b".to_owned(), "line one\nline two".to_owned()),
+            ("c".to_owned(), "folded text".to_owned()),
+            ("d".to_owned(), "plain".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn reads_quoted_scalars_over_several_lines() {
+    let text = "\
+a: \"one
+  two\\
+  three
+
+  four\" # done
+b: 'it''s
+  all'
+c: \"\\x41\\u00e9\\ttab\"
+";
+    assert_eq!(
+        YamlFormat.entries(text).unwrap(),
+        [
+            ("a".to_owned(), "one twothree\nfour".to_owned()),
+            ("b".to_owned(), "it's all".to_owned()),
+            ("c".to_owned(), "A\u{e9}\ttab".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn refuses_unterminated_quotes() {
+    for text in ["a: '\n", "a: 'abc\n", "a: \"abc\nb: c\n", "a: 'x' y\n"] {
+        let error = YamlFormat.entries(text).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 1, .. }), "{text}");
+    }
+}
+
+#[test]
+fn inserts_before_comments_of_the_target() {
+    let text = YamlFormat.insert(WRAPPED, "home", "about", "Over").unwrap();
+    assert!(text.starts_with("nl:\n  about: Over\n  # the start page\n  home:\n"));
+    let error = YamlFormat
+        .insert(WRAPPED, "home", "bye", "Dag")
+        .unwrap_err();
+    assert!(matches!(error, Error::KeyExists(_)));
+}
+
+#[test]
+fn appends_into_existing_mappings() {
+    let text = YamlFormat
+        .append(WRAPPED, "home.outro", "Tot ziens")
+        .unwrap();
+    assert!(
+        text.contains("    intro: \"Welkom: fijn\" # shown once\n    outro: Tot ziens\n  bye: Dag")
+    );
+    let text = YamlFormat.append(WRAPPED, "menu.open", "Open").unwrap();
+    assert!(text.ends_with("  bye: Dag\n  menu:\n    open: Open"));
+}
+
+#[test]
+fn refuses_to_append_below_a_scalar() {
+    let error = YamlFormat.append(WRAPPED, "bye.later", "x").unwrap_err();
+    assert!(matches!(error, Error::KeyExists(key) if key == "bye"));
+}
+
+#[test]
+fn removes_mappings_with_their_children() {
+    assert_eq!(
+        YamlFormat.remove(WRAPPED, "home").unwrap(),
+        "nl:\n  # the start page\n  bye: Dag"
+    );
+}
+
+#[test]
+fn replaces_keeping_the_comment() {
+    let text = YamlFormat.replace(WRAPPED, "home.intro", "Hoi").unwrap();
+    assert!(text.contains("    intro: Hoi # shown once\n"));
+}
+
+#[test]
+fn quotes_values_that_would_read_differently() {
+    for value in [
+        "yes",
+        "12",
+        "- item",
+        "a: b",
+        "x #y",
+        " pad",
+        "multi\nline",
+        "",
+        "n",
+        "Y",
+        ".inf",
+        ".NaN",
+        "0x1f",
+        "1_000",
+        "1:30",
+    ] {
+        let text = YamlFormat.replace("a: b\n", "a", value).unwrap();
+        assert_eq!(YamlFormat.entries(&text).unwrap()[0].1, value, "{text}");
+        assert!(text.starts_with("a: \""), "{text}");
+    }
+    assert_eq!(YamlFormat.replace("a: b\n", "a", "c d").unwrap(), "a: c d");
+}
+
+#[test]
+fn refuses_flow_mappings() {
+    let error = YamlFormat.entries("nl: { home: Start }\n").unwrap_err();
+    assert!(matches!(error, Error::Parse { line: 1, .. }));
+    let error = YamlFormat.append("a: b\nc: {}\n", "d", "e").unwrap_err();
+    assert!(matches!(error, Error::Parse { line: 2, .. }));
+    assert_eq!(YamlFormat.find("{a: b}", "a"), None);
+}
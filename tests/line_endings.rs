@@ -0,0 +1,92 @@
+use beetree::lang::{self, FindSpecified};
+use beetree::layout::Layout;
+use beetree::Input;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/line_endings");
+
+/// copies the fixture into `<tmp>/<name>/en/lang.env` and returns the base path
+fn setup(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join(format!("beetree-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("en")).unwrap();
+    fs::copy(
+        Path::new(FIXTURES).join(format!("{name}.env")),
+        base.join("en/lang.env"),
+    )
+    .unwrap();
+    base
+}
+
+/// inserts NEW before B, removes A and appends Z
+fn edit(base: &Path) {
+    lang::insert(lang::InsertConfig {
+        verbose: false,
+        base_path: base.to_owned(),
+        layout: Layout::default(),
+        input: Input::Text("en,new".into()),
+        src_tag: "NEW".into(),
+        serializer: None,
+        dst_tag: FindSpecified {
+            needle: "B".into(),
+            file: None,
+        },
+    })
+    .unwrap();
+    lang::remove(lang::RemoveConfig {
+        verbose: false,
+        base_path: base.to_owned(),
+        layout: Layout::default(),
+        dst_tag: FindSpecified {
+            needle: "A".into(),
+            file: None,
+        },
+        languages: "en".into(),
+        yes: true,
+    })
+    .unwrap();
+    lang::append(lang::AppendConfig {
+        verbose: false,
+        base_path: base.to_owned(),
+        layout: Layout::default(),
+        file: "lang.env".into(),
+        input: Input::Text("en,z".into()),
+        src_tag: "Z".into(),
+        serializer: None,
+    })
+    .unwrap();
+}
+
+fn check(name: &str) {
+    let base = setup(name);
+    edit(&base);
+    let actual = fs::read(base.join("en/lang.env")).unwrap();
+    let expected = fs::read(Path::new(FIXTURES).join(format!("{name}.expected.env"))).unwrap();
+    fs::remove_dir_all(&base).unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&actual),
+        String::from_utf8_lossy(&expected)
+    );
+}
+
+#[test]
+fn keeps_lf() {
+    check("lf");
+}
+#[test]
+fn keeps_crlf() {
+    check("crlf");
+}
+#[test]
+fn keeps_bom() {
+    check("bom");
+}
+#[test]
+fn keeps_missing_final_newline() {
+    check("no_final_newline");
+}
+#[test]
+fn keeps_bom_crlf_and_missing_final_newline() {
+    check("bom_crlf_no_final_newline");
+}
@@ -0,0 +1,106 @@
+use beetree::serializer::ValueSerializer;
+
+#[test]
+fn parses_names() {
+    assert_eq!(
+        "env".parse::<ValueSerializer>().unwrap(),
+        ValueSerializer::Dotenv
+    );
+    assert_eq!(
+        "ts".parse::<ValueSerializer>().unwrap(),
+        ValueSerializer::Js
+    );
+    assert!("toml".parse::<ValueSerializer>().is_err());
+}
+
+#[test]
+fn infers_from_path() {
+    assert_eq!(
+        ValueSerializer::from_path("en/lang.env"),
+        ValueSerializer::Dotenv
+    );
+    assert_eq!(
+        ValueSerializer::from_path("en/lang.json"),
+        ValueSerializer::Json
+    );
+    assert_eq!(
+        ValueSerializer::from_path("en/lang.tsx"),
+        ValueSerializer::Js
+    );
+    assert_eq!(
+        ValueSerializer::from_path("messages.properties"),
+        ValueSerializer::Properties
+    );
+    assert_eq!(
+        ValueSerializer::resolve(Some(ValueSerializer::Json), "lang.env"),
+        ValueSerializer::Json
+    );
+}
+
+#[test]
+fn escapes_dotenv() {
+    let value = "say \"hi\"\n\tto $USER \\ o/";
+    let quoted = ValueSerializer::Dotenv.quote(value);
+    assert_eq!(quoted, r#""say \"hi\"\n\tto \$USER \\ o/""#);
+    assert_eq!(ValueSerializer::Dotenv.unquote(&quoted), value);
+    assert_eq!(ValueSerializer::Dotenv.entry("A", "b"), "A=\"b\"");
+}
+
+#[test]
+fn reads_other_dotenv_quoting() {
+    assert_eq!(ValueSerializer::Dotenv.unquote("'it is \\n'"), "it is \\n");
+    assert_eq!(ValueSerializer::Dotenv.unquote(" bare "), "bare");
+}
+
+#[test]
+fn escapes_json() {
+    let value = "a \"quote\"\nand \u{2028}";
+    let quoted = ValueSerializer::Json.quote(value);
+    assert_eq!(quoted, "\"a \\\"quote\\\"\\nand \u{2028}\"");
+    assert_eq!(ValueSerializer::Json.unquote(&quoted), value);
+    assert_eq!(
+        ValueSerializer::Json.entry("greeting", "hi"),
+        "\"greeting\": \"hi\","
+    );
+}
+
+#[test]
+fn escapes_js_line_separators() {
+    let quoted = ValueSerializer::Js.quote("a\u{2028}b\u{2029}c");
+    assert_eq!(quoted, "\"a\\u2028b\\u2029c\"");
+    assert_eq!(ValueSerializer::Js.unquote(&quoted), "a\u{2028}b\u{2029}c");
+    assert_eq!(ValueSerializer::Js.unquote("'it\\'s'"), "it's");
+}
+
+#[test]
+fn quotes_js_keys_only_when_needed() {
+    assert_eq!(ValueSerializer::Js.entry("$ok_1", "v"), "$ok_1: \"v\",");
+    assert_eq!(
+        ValueSerializer::Js.entry("not-ok", "v"),
+        "\"not-ok\": \"v\","
+    );
+    assert_eq!(ValueSerializer::Js.entry("1st", "v"), "\"1st\": \"v\",");
+}
+
+#[test]
+fn escapes_properties() {
+    let quoted = ValueSerializer::Properties.quote(" lead=x\té");
+    assert_eq!(quoted, "\\ lead=x\\t\\u00E9");
+    assert_eq!(ValueSerializer::Properties.unquote(&quoted), " lead=x\té");
+    assert_eq!(
+        ValueSerializer::Properties.entry("a key:1", "v"),
+        "a\\ key\\:1=v"
+    );
+}
+
+#[test]
+fn escapes_properties_surrogate_pairs() {
+    let quoted = ValueSerializer::Properties.quote("😀");
+    assert_eq!(quoted, "\\uD83D\\uDE00");
+    assert_eq!(ValueSerializer::Properties.unquote(&quoted), "😀");
+}
+
+#[test]
+fn keeps_escaped_trailing_space_of_properties() {
+    assert_eq!(ValueSerializer::Properties.unquote("  value\\ "), "value ");
+}
@@ -0,0 +1,73 @@
+use beetree::usage::{Ledger, Usage};
+use std::fs;
+use std::thread;
+
+/// a ledger in a fresh directory below the temp dir
+fn ledger(name: &str) -> Ledger {
+    let dir = std::env::temp_dir().join(format!("beetree-ledger-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    Ledger {
+        path: dir.join("ledger.tsv"),
+    }
+}
+
+fn usage(prompt_tokens: u64, completion_tokens: u64) -> Usage {
+    Usage {
+        prompt_tokens,
+        completion_tokens,
+    }
+}
+
+#[test]
+fn adds_a_line_per_request() {
+    let ledger = ledger("lines");
+    assert!(ledger.entries().unwrap().is_empty());
+    ledger
+        .add("2026-10", "gpt-4o-mini", usage(100, 20), Some(0.5))
+        .unwrap();
+    ledger
+        .add("2026-10", "gpt-4o-mini", usage(10, 2), None)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&ledger.path).unwrap(),
+        "2026-10\tgpt-4o-mini\t100\t20\t0.5\n2026-10\tgpt-4o-mini\t10\t2\t-\n"
+    );
+    let entries = ledger.entries().unwrap();
+    assert_eq!(entries[0].cost, Some(0.5));
+    assert_eq!(entries[1].usage, usage(10, 2));
+    assert_eq!(entries[1].cost, None);
+}
+
+#[test]
+fn keeps_the_requests_of_commands_running_at_once() {
+    let ledger = ledger("parallel");
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..50 {
+                    ledger
+                        .add("2026-10", "fake-model", usage(3, 1), None)
+                        .unwrap();
+                }
+            });
+        }
+    });
+    let entries = ledger.entries().unwrap();
+    assert_eq!(entries.len(), 200);
+    assert!(entries.iter().all(|e| e.usage == usage(3, 1)));
+}
+
+#[test]
+fn reports_the_line_of_a_broken_entry() {
+    let ledger = ledger("broken");
+    ledger
+        .add("2026-10", "fake-model", usage(3, 1), None)
+        .unwrap();
+    fs::write(
+        &ledger.path,
+        fs::read_to_string(&ledger.path).unwrap() + "2026-10\tfake-model\tmany\n",
+    )
+    .unwrap();
+    let error = ledger.entries().unwrap_err().to_string();
+    assert!(error.starts_with("invalid line 2"), "{error}");
+}
@@ -0,0 +1,140 @@
+use beetree::format::xliff::{self, Document, File, Unit, Version};
+use beetree::format::xml;
+use beetree::lang;
+use beetree::layout::Layout;
+use std::fs;
+use std::path::PathBuf;
+
+fn document(version: Version) -> Document {
+    Document {
+        version,
+        source_language: "en".into(),
+        target_language: "de".into(),
+        files: vec![File {
+            original: "app.json".into(),
+            units: vec![
+                Unit {
+                    key: "home.title".into(),
+                    source: "Tom & <Jerry>".into(),
+                    target: Some("Tom & \"Jerry\"".into()),
+                },
+                Unit {
+                    key: "bye".into(),
+                    source: "  Bye\nnow ".into(),
+                    target: None,
+                },
+            ],
+        }],
+    }
+}
+
+/// an empty base directory below the temp dir
+fn base(name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join(format!("beetree-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    base
+}
+
+#[test]
+fn round_trips_both_versions() {
+    for version in [Version::V1_2, Version::V2_0] {
+        let text = xliff::write(&document(version));
+        let doc = xliff::read(&text).unwrap();
+        assert_eq!(doc.version, version);
+        assert_eq!(
+            (doc.source_language.as_str(), doc.target_language.as_str()),
+            ("en", "de")
+        );
+        let units = &doc.files[0].units;
+        assert_eq!(doc.files[0].original, "app.json");
+        assert_eq!(units[0].key, "home.title");
+        assert_eq!(units[0].source, "Tom & <Jerry>");
+        assert_eq!(units[0].target.as_deref(), Some("Tom & \"Jerry\""));
+        assert_eq!(units[1].source, "  Bye\nnow ");
+        assert_eq!(units[1].target, None);
+    }
+}
+
+#[test]
+fn escapes_markup_in_text() {
+    let text = xliff::write(&document(Version::V1_2));
+    assert!(text.contains("<source>Tom &amp; &lt;Jerry&gt;</source>"));
+    assert_eq!(xml::unescape("&lt;b&gt; &#233;&#x20AC;"), "<b> é€");
+}
+
+#[test]
+fn reads_units_in_groups_and_segments() {
+    let text = r#"<xliff version="2.0" srcLang="en" trgLang="fr">
+  <file id="f1" original="app.json">
+    <group id="g1">
+      <unit id="u1" name="a">
+        <segment><source>One. </source><target>Un. </target></segment>
+        <ignorable><source> </source></ignorable>
+        <segment><source>Two.</source><target>Deux.</target></segment>
+      </unit>
+    </group>
+  </file>
+</xliff>"#;
+    let doc = xliff::read(text).unwrap();
+    let unit = &doc.files[0].units[0];
+    assert_eq!(unit.key, "a");
+    assert_eq!(unit.source, "One.  Two.");
+    assert_eq!(unit.target.as_deref(), Some("Un. Deux."));
+}
+
+#[test]
+fn refuses_other_documents() {
+    assert!(xliff::read("<resources></resources>").is_err());
+    assert!(xliff::read(r#"<xliff version="3.0"></xliff>"#).is_err());
+}
+
+#[test]
+fn imports_into_a_new_language_file() {
+    let base = base("xliff-import");
+    fs::create_dir_all(base.join("en")).unwrap();
+    fs::write(
+        base.join("en/app.json"),
+        "{\n  \"home\": {\n    \"title\": \"Tom & <Jerry>\"\n  }\n}\n",
+    )
+    .unwrap();
+    let input = base.join("de.xlf");
+    fs::write(&input, xliff::write(&document(Version::V2_0))).unwrap();
+    lang::import_xliff(lang::ImportXliffConfig {
+        verbose: false,
+        base_path: base.clone(),
+        layout: "{base}/{lang}/{file}".parse::<Layout>().unwrap(),
+        input,
+        serializer: None,
+    })
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(base.join("de/app.json")).unwrap(),
+        "{\n  \"home\": {\n    \"title\": \"Tom & \\\"Jerry\\\"\"\n  }\n}\n"
+    );
+}
+
+#[test]
+fn imports_a_new_language_laid_out_like_the_source() {
+    let json = "{\n  \"home\": {\n    \"title\": \"Tom & <Jerry>\"\n  }\n}\n";
+    for (source, target) in [
+        ("en/app.json", "de/app.json"),
+        ("app_en.json", "app_de.json"),
+    ] {
+        let base = base("xliff-new-lang");
+        let source = base.join(source);
+        fs::create_dir_all(source.parent().unwrap()).unwrap();
+        fs::write(&source, json).unwrap();
+        let input = base.join("de.xlf");
+        fs::write(&input, xliff::write(&document(Version::V1_2))).unwrap();
+        lang::import_xliff(lang::ImportXliffConfig {
+            verbose: false,
+            base_path: base.clone(),
+            layout: Layout::default(),
+            input,
+            serializer: None,
+        })
+        .unwrap();
+        assert!(base.join(target).is_file(), "{target}");
+    }
+}
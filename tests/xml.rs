@@ -0,0 +1,39 @@
+use beetree::format::xml;
+
+#[test]
+fn parses_elements_with_their_ranges() {
+    let text = "<?xml version=\"1.0\"?>\n<!-- c -->\n<a x='1' y=\"&lt;2&gt;\">\n  <b/>\n  <c>t</c>\n</a>\n";
+    let root = xml::parse(text).unwrap();
+    assert_eq!(root.name, "a");
+    assert_eq!(root.attribute("x"), Some("1"));
+    assert_eq!(root.attribute("y"), Some("<2>"));
+    assert_eq!(root.children.len(), 2);
+    let c = root.child("c").unwrap();
+    assert_eq!(&text[c.start..c.end], "<c>t</c>");
+    assert_eq!(&text[c.inner.clone()], "t");
+    assert!(root.child("b").unwrap().inner.is_empty());
+}
+
+#[test]
+fn reads_text_with_cdata_and_entities() {
+    let text = "<a>x &amp; <![CDATA[<raw> &amp;]]><!-- no --><i>y</i> &#x41;&#66;</a>";
+    let root = xml::parse(text).unwrap();
+    assert_eq!(root.text(text), "x & <raw> &amp;y AB");
+}
+
+#[test]
+fn escapes_and_unescapes() {
+    assert_eq!(
+        xml::escape("<a href=\"x\">&</a>"),
+        "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+    );
+    assert_eq!(xml::unescape("&quot;&apos;&unknown; &"), "\"'&unknown; &");
+}
+
+#[test]
+fn reports_the_line_of_errors() {
+    let error = xml::parse("<a>\n<b>\n</a>").unwrap_err().to_string();
+    assert!(error.contains("line 3"), "{error}");
+    assert!(xml::parse("<a></a><b/>").is_err());
+    assert!(xml::parse("<a x=1/>").is_err());
+}